@@ -0,0 +1,72 @@
+//! The private [AuditEntry] trail the engine can keep next to the public
+//! [crate::History] of a game.
+//!
+//! Bots never see the audit trail. It records the real hand of a bot at each
+//! claim, the cards exchanged with the deck and the order of the deck after
+//! each shuffle so you can tell afterwards which claims were bluffs.
+//!
+//! ```rust
+//! use coup::{bots::RandomBot, Coup};
+//!
+//! let mut coup_game = Coup::new(vec![Box::new(RandomBot), Box::new(RandomBot)]);
+//! coup_game.set_audit(true);
+//! coup_game.play();
+//!
+//! let bluffs = coup_game
+//!     .audit()
+//!     .iter()
+//!     .filter(|entry| entry.event.truthful() == Some(false))
+//!     .count();
+//! println!("The bots bluffed {} times", bluffs);
+//! ```
+
+use crate::Card;
+
+/// A single entry of the audit trail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+	/// The amount of [crate::History] events before this entry.
+	/// For a claim this is the index of the event the claim was made with.
+	pub history: usize,
+	/// What happened
+	pub event: AuditEvent,
+}
+
+/// Something only the engine knows about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditEvent {
+	/// The deck after it was shuffled with the top card last
+	Shuffle { deck: Vec<Card> },
+	/// A bot claimed to have one of these cards with an action or a counter
+	Claim {
+		by: String,
+		cards: Vec<Card>,
+		hand: Vec<Card>,
+	},
+	/// A bot claimed not to have this card with an action
+	Disclaim {
+		by: String,
+		card: Card,
+		hand: Vec<Card>,
+	},
+	/// A bot drew cards from the deck and returned others to it
+	Exchange {
+		by: String,
+		drawn: Vec<Card>,
+		returned: Vec<Card>,
+	},
+}
+
+impl AuditEvent {
+	/// Whether a claim was true or a bluff.
+	/// Returns `None` for everything that isn't a claim.
+	pub fn truthful(&self) -> Option<bool> {
+		match self {
+			AuditEvent::Claim { cards, hand, .. } => {
+				Some(cards.iter().any(|card| hand.contains(card)))
+			},
+			AuditEvent::Disclaim { card, hand, .. } => Some(!hand.contains(card)),
+			AuditEvent::Shuffle { .. } | AuditEvent::Exchange { .. } => None,
+		}
+	}
+}
@@ -0,0 +1,388 @@
+//! The [Belief] of a bot about the cards it can't see.
+//!
+//! A belief starts from the deck of the game, removes all cards the bot can
+//! see in its hand, the discard pile and the cards it set aside and then weighs
+//! the claims other bots made in the [History] to tell how likely each opponent
+//! holds each [Card]. Cards the bot examined with the Inquisitor are known to
+//! be in the hand of their owner until that owner's hand changes.
+//!
+//! ```rust
+//! use coup::{
+//!     belief::Belief,
+//!     bot::{BotInterface, Context},
+//!     Action, Card,
+//! };
+//!
+//! pub struct CountingBot;
+//!
+//! impl BotInterface for CountingBot {
+//!     fn get_name(&self) -> String {
+//!         String::from("CountingBot")
+//!     }
+//!
+//!     // Only challenge a Duke when it's unlikely the bot has one
+//!     fn on_challenge_action_round(
+//!         &self,
+//!         action: &Action,
+//!         by: String,
+//!         context: &Context,
+//!     ) -> bool {
+//!         *action == Action::Tax
+//!             && Belief::new(context).probability(&by, Card::Duke) < 0.25
+//!     }
+//! }
+//! ```
+
+use crate::{
+	bot::{Context, KnownCardSource},
+	role::RoleSet,
+	Action, Card, Counter, History,
+};
+
+/// What a bot can tell about the hidden cards of a game from its [Context].
+///
+/// Keep a belief around between callbacks and [Belief::update] it with each
+/// new context to only read the events that happened in between.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Belief {
+	/// How much a claim is trusted from 0 where claims mean nothing to 1 where
+	/// bots never bluff.
+	/// Defaults to 0.5.
+	pub claim_trust: f64,
+	roles: RoleSet,
+	name: String,
+	unseen: Vec<Card>,
+	hands: Vec<(String, u8)>,
+	claims: Vec<(String, Vec<Card>)>,
+	disclaims: Vec<(String, Card)>,
+	examined: Vec<(String, Card)>,
+	observed: usize,
+}
+
+impl Belief {
+	/// The belief of the bot this context was passed to.
+	pub fn new(context: &Context) -> Self {
+		let mut belief = Self {
+			claim_trust: 0.5,
+			roles: context.roles.clone(),
+			name: context.name.clone(),
+			unseen: vec![],
+			hands: vec![],
+			claims: vec![],
+			disclaims: vec![],
+			examined: vec![],
+			observed: 0,
+		};
+		belief.update(context);
+		belief
+	}
+
+	/// Catch up with a newer context of the same bot.
+	/// Only the events that weren't observed yet are read from its history and
+	/// a context of a new game starts the belief over.
+	pub fn update(&mut self, context: &Context) {
+		if context.history.len() < self.observed || context.name != self.name {
+			self.claims.clear();
+			self.disclaims.clear();
+			self.observed = 0;
+		}
+		self.roles = context.roles.clone();
+		self.name = context.name.clone();
+
+		// Everything in the deck that isn't in our hand, the discard pile or set
+		// aside by us
+		self.unseen = context.roles.deck(context.seats.len());
+		let set_aside = context
+			.known_cards
+			.iter()
+			.filter(|known| known.source == KnownCardSource::SetAside)
+			.map(|known| &known.card);
+		for card in
+			context.cards.iter().chain(context.discard_pile.iter()).chain(set_aside)
+		{
+			if let Some(index) = self.unseen.iter().position(|c| c == card) {
+				self.unseen.remove(index);
+			}
+		}
+
+		self.hands = context
+			.playing_bots
+			.iter()
+			.filter(|bot| bot.name != context.name)
+			.map(|bot| (bot.name.clone(), bot.cards))
+			.collect();
+
+		for event in context.history[self.observed..].iter() {
+			self.observe(event);
+		}
+		self.observed = context.history.len();
+		self.examined = examined(context);
+	}
+
+	/// Weigh a single event.
+	/// Actions and counters are claims of a card, a challenge settles the claim
+	/// it challenged and an exchange gives a bot a new hand so everything it
+	/// claimed before is forgotten.
+	pub fn observe(&mut self, event: &History) {
+		match event {
+			History::ActionAssassination { by, .. } => {
+				self.claim(by, &Action::Assassination(String::new()))
+			},
+			History::ActionStealing { by, .. } => {
+				self.claim(by, &Action::Stealing(String::new()))
+			},
+			History::ActionTax { by } => self.claim(by, &Action::Tax),
+			History::ActionExamine { by, .. } => {
+				self.claim(by, &Action::Examine(String::new()))
+			},
+			History::ActionSwapping { by } => {
+				self.forget(by);
+				self.claim(by, &Action::Swapping);
+			},
+			History::ActionEmbezzlement { by } => {
+				if let Some(card) = self.roles.disclaimed_card(&Action::Embezzlement) {
+					self.disclaims.push((by.clone(), card));
+				}
+			},
+			History::ActionExamineSwap { target, .. } => self.forget(target),
+			History::ChallengeAssassin { target, .. } => {
+				self.settle(target, &[Card::Assassin])
+			},
+			History::ChallengeAmbassador { target, .. } => {
+				self.settle(target, &[Card::Ambassador])
+			},
+			History::ChallengeCaptain { target, .. } => {
+				self.settle(target, &[Card::Captain])
+			},
+			History::ChallengeDuke { target, .. } => {
+				self.settle(target, &[Card::Duke])
+			},
+			History::ChallengeInquisitor { target, .. } => {
+				self.settle(target, &[Card::Inquisitor])
+			},
+			History::ChallengeContessa { target, .. } => {
+				self.settle(target, &[Card::Contessa])
+			},
+			History::ChallengeEmbezzlement { target, .. } => {
+				self.disclaims.retain(|(name, _)| name != target)
+			},
+			History::CounterAssassination { by, .. } => {
+				self.counter_claim(by, Counter::Assassination)
+			},
+			History::CounterForeignAid { by, .. } => {
+				self.counter_claim(by, Counter::ForeignAid)
+			},
+			History::CounterStealing { by, .. } => {
+				self.counter_claim(by, Counter::Stealing)
+			},
+			History::CounterChallengeContessa { target, .. } => {
+				self.settle(target, &[Card::Contessa])
+			},
+			History::CounterChallengeDuke { target, .. } => {
+				self.settle(target, &[Card::Duke])
+			},
+			History::CounterChallengeCaptainAmbassedor { target, .. } => self
+				.settle(target, &[Card::Captain, Card::Ambassador, Card::Inquisitor]),
+			History::CounterChallengeAssassin { target, .. } => {
+				self.settle(target, &[Card::Assassin])
+			},
+			History::ActionCoup { .. }
+			| History::ActionForeignAid { .. }
+			| History::ActionIncome { .. }
+			| History::ActionConversion { .. }
+			| History::Draft { .. }
+			| History::CardLoss { .. } => {},
+		}
+	}
+
+	/// All cards of the deck that you can't see in your hand or the discard pile.
+	/// These are either in the hand of another bot or still in the deck.
+	pub fn unseen(&self) -> &[Card] {
+		&self.unseen
+	}
+
+	/// How many copies of this card you can't see.
+	pub fn unseen_count(&self, card: Card) -> usize {
+		self.unseen.iter().filter(|unseen| **unseen == card).count()
+	}
+
+	/// The probability between 0 and 1 that this opponent holds at least one
+	/// copy of the card.
+	/// Bots that are out of the game or unknown hold nothing and a card you
+	/// examined in their hand is certain.
+	pub fn probability(&self, opponent: &str, card: Card) -> f64 {
+		let cards = self
+			.hands
+			.iter()
+			.find(|(name, _)| name == opponent)
+			.map_or(0, |(_, cards)| *cards as usize);
+		if cards == 0 {
+			return 0.0;
+		}
+		if self.examined.contains(&(opponent.to_string(), card)) {
+			return 1.0;
+		}
+
+		// Examined cards are neither in the deck nor dealt to anyone else
+		let cards = cards.saturating_sub(
+			self.examined.iter().filter(|(name, _)| name == opponent).count(),
+		);
+		let copies = self.unseen_count(card).saturating_sub(
+			self.examined.iter().filter(|(_, examined)| *examined == card).count(),
+		);
+		if cards == 0 || copies == 0 {
+			return 0.0;
+		}
+
+		// The chance none of its cards is a copy when dealt from the unseen cards
+		let unseen = self.unseen.len().saturating_sub(self.examined.len());
+		let mut none = 1.0;
+		for drawn in 0..cards {
+			none *= unseen.saturating_sub(copies + drawn) as f64
+				/ unseen.saturating_sub(drawn).max(1) as f64;
+		}
+
+		// Claims of other cards that could have been played instead count less
+		for (_, claim) in self
+			.claims
+			.iter()
+			.filter(|(name, claim)| name == opponent && claim.contains(&card))
+		{
+			none *= 1.0 - self.claim_trust / claim.len() as f64;
+		}
+
+		let mut probability = 1.0 - none;
+		if self.disclaims.contains(&(opponent.to_string(), card)) {
+			probability *= 1.0 - self.claim_trust;
+		}
+		probability
+	}
+
+	/// The probability of each card of the roles of this game for this opponent.
+	pub fn probabilities(&self, opponent: &str) -> Vec<(Card, f64)> {
+		self
+			.roles
+			.cards()
+			.into_iter()
+			.map(|card| (card, self.probability(opponent, card)))
+			.collect()
+	}
+
+	fn claim(&mut self, by: &str, action: &Action) {
+		if let Some(card) = self.roles.claimed_card(action) {
+			self.add_claim(by, vec![card]);
+		}
+	}
+
+	fn counter_claim(&mut self, by: &str, counter: Counter) {
+		let blockers = self.counter_blockers(counter);
+		if !blockers.is_empty() {
+			self.add_claim(by, blockers);
+		}
+	}
+
+	// Claiming the same thing twice doesn't make it more true
+	fn add_claim(&mut self, by: &str, cards: Vec<Card>) {
+		let claim = (by.to_string(), cards);
+		if !self.claims.contains(&claim) {
+			self.claims.push(claim);
+		}
+	}
+
+	fn counter_blockers(&self, counter: Counter) -> Vec<Card> {
+		let action = match counter {
+			Counter::Assassination => Action::Assassination(String::new()),
+			Counter::ForeignAid => Action::ForeignAid,
+			Counter::Stealing => Action::Stealing(String::new()),
+		};
+		self.roles.blockers(&action)
+	}
+
+	// After a challenge a bot either lost the card it bluffed or swapped the card
+	// it had so the claim doesn't tell anything anymore
+	fn settle(&mut self, target: &str, cards: &[Card]) {
+		self.claims.retain(|(name, claim)| {
+			name != target || !claim.iter().any(|card| cards.contains(card))
+		});
+	}
+
+	fn forget(&mut self, name: &str) {
+		self.claims.retain(|(claimer, _)| claimer != name);
+		self.disclaims.retain(|(claimer, _)| claimer != name);
+	}
+}
+
+// The last card we examined of each opponent as long as its hand didn't change
+// since. Examines that didn't show us a card can't be told apart from the ones
+// that did so an opponent is left out when the two don't add up.
+fn examined(context: &Context) -> Vec<(String, Card)> {
+	let mut examined: Vec<(String, Card)> = Vec::new();
+	for known in context.known_cards.iter() {
+		if let KnownCardSource::Examined { owner } = &known.source {
+			examined.retain(|(name, _)| name != owner);
+			examined.push((owner.clone(), known.card));
+		}
+	}
+
+	examined.retain(|(owner, card)| {
+		let examines = context
+			.history
+			.iter()
+			.enumerate()
+			.filter(|(_, event)| {
+				matches!(event, History::ActionExamine { by, target }
+					if *by == context.name && target == owner)
+			})
+			.map(|(index, _)| index)
+			.collect::<Vec<usize>>();
+		let shown = context
+			.known_cards
+			.iter()
+			.filter(|known| {
+				matches!(&known.source, KnownCardSource::Examined { owner: name }
+					if name == owner)
+			})
+			.count();
+		examines.len() == shown
+			&& examines.last().is_some_and(|last| {
+				!context.history[last + 1..]
+					.iter()
+					.any(|event| changes_hand(event, owner, *card))
+			})
+	});
+	examined
+}
+
+// Whether this event could have taken the card out of the hand of the owner
+fn changes_hand(event: &History, owner: &str, card: Card) -> bool {
+	match event {
+		History::ActionSwapping { by } => by == owner,
+		History::ActionExamineSwap { target, .. } => target == owner,
+		History::CardLoss { by, card: lost } => by == owner && *lost == card,
+		// Winning a challenge shuffles the revealed card into the deck
+		History::ChallengeAssassin { target, .. }
+		| History::ChallengeAmbassador { target, .. }
+		| History::ChallengeCaptain { target, .. }
+		| History::ChallengeDuke { target, .. }
+		| History::ChallengeEmbezzlement { target, .. }
+		| History::ChallengeInquisitor { target, .. }
+		| History::ChallengeContessa { target, .. }
+		| History::CounterChallengeContessa { target, .. }
+		| History::CounterChallengeDuke { target, .. }
+		| History::CounterChallengeCaptainAmbassedor { target, .. }
+		| History::CounterChallengeAssassin { target, .. } => target == owner,
+		History::ActionAssassination { .. }
+		| History::ActionCoup { .. }
+		| History::ActionForeignAid { .. }
+		| History::ActionIncome { .. }
+		| History::ActionStealing { .. }
+		| History::ActionTax { .. }
+		| History::ActionConversion { .. }
+		| History::ActionEmbezzlement { .. }
+		| History::ActionExamine { .. }
+		| History::CounterAssassination { .. }
+		| History::CounterForeignAid { .. }
+		| History::CounterStealing { .. }
+		| History::Draft { .. } => false,
+	}
+}
@@ -0,0 +1,269 @@
+//! The [BluffReport] of each bot built from the real hands at each claim in the
+//! games of a [crate::Coup::looping] run.
+//! Turn it on with [crate::Coup::set_bluff_report].
+//!
+//! Unlike a [crate::profile::ClaimProfile] it knows the real hands of the bots
+//! so it counts every bluff, not only the ones that were caught.
+//!
+//! ```rust
+//! use coup::{bots::RandomBot, Card, Coup};
+//!
+//! let mut coup_game = Coup::new(vec![Box::new(RandomBot), Box::new(RandomBot)]);
+//! coup_game.set_bluff_report(true);
+//! coup_game.looping(10);
+//!
+//! for stats in coup_game.bluff_report().unwrap().bots.iter() {
+//!     println!(
+//!         "{} bluffed the Duke {} times and got challenged {} times",
+//!         stats.name,
+//!         stats.bluffed(Card::Duke),
+//!         stats.bluffs_challenged(Card::Duke)
+//!     );
+//! }
+//! ```
+
+use std::fmt;
+
+use crate::{
+	audit::{AuditEntry, AuditEvent},
+	Card, History,
+};
+
+/// The bluffs of a single bot over all games of a report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BluffStats {
+	/// The name of the bot
+	pub name: String,
+	/// The amount of games this bot played
+	pub games: usize,
+	/// How often this bot bluffed each card with an action.
+	/// Claiming not to have a card while having it counts for that card.
+	pub bluffs: Vec<(Card, usize)>,
+	/// How often a bluff of each card with an action was challenged
+	pub challenged_bluffs: Vec<(Card, usize)>,
+	/// How often this bot challenged an action or counter of another bot
+	pub challenges: usize,
+	/// How often this bot challenged an action or counter that wasn't a bluff
+	pub truthful_challenges: usize,
+	/// How often this bot countered with a card it had
+	pub real_counters: usize,
+	/// How often this bot countered with a card it didn't have
+	pub bluffed_counters: usize,
+}
+
+impl BluffStats {
+	fn new(name: String) -> Self {
+		Self {
+			name,
+			games: 0,
+			bluffs: vec![],
+			challenged_bluffs: vec![],
+			challenges: 0,
+			truthful_challenges: 0,
+			real_counters: 0,
+			bluffed_counters: 0,
+		}
+	}
+
+	/// How often this bot bluffed the card with an action.
+	pub fn bluffed(&self, card: Card) -> usize {
+		count_of(&self.bluffs, card)
+	}
+
+	/// How often a bluff of the card with an action was challenged.
+	pub fn bluffs_challenged(&self, card: Card) -> usize {
+		count_of(&self.challenged_bluffs, card)
+	}
+
+	/// How often this bot bluffed with an action.
+	pub fn total_bluffs(&self) -> usize {
+		self.bluffs.iter().map(|(_, count)| count).sum()
+	}
+
+	/// How often a bluff of this bot with an action was challenged.
+	pub fn total_bluffs_challenged(&self) -> usize {
+		self.challenged_bluffs.iter().map(|(_, count)| count).sum()
+	}
+}
+
+/// The [BluffStats] of each bot in the order they first played.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BluffReport {
+	/// The stats of each bot
+	pub bots: Vec<BluffStats>,
+}
+
+// The last claim made at the table and whether it was true
+struct OpenClaim {
+	by: String,
+	bluffed_card: Option<Card>,
+	truthful: bool,
+}
+
+impl BluffReport {
+	/// Add a game by its history and the claims of the audit trail it was
+	/// played with.
+	///
+	/// A challenge always targets the claim of the action or counter right
+	/// before it.
+	pub fn add_game(
+		&mut self,
+		seated: &[String],
+		history: &[History],
+		audit: &[AuditEntry],
+	) {
+		for name in seated.iter() {
+			self.stats_of(name).games += 1;
+		}
+
+		// The first claim made with each event of the history
+		let mut claims: Vec<Option<&AuditEvent>> = vec![None; history.len()];
+		for entry in audit.iter().filter(|entry| entry.event.truthful().is_some()) {
+			if let Some(claim) = claims.get_mut(entry.history) {
+				claim.get_or_insert(&entry.event);
+			}
+		}
+
+		let mut open_claim: Option<OpenClaim> = None;
+		for (index, event) in history.iter().enumerate() {
+			if let Some(claim) = claims[index] {
+				let truthful = claim.truthful() == Some(true);
+				let is_counter = matches!(
+					event,
+					History::CounterAssassination { .. }
+						| History::CounterForeignAid { .. }
+						| History::CounterStealing { .. }
+				);
+				let (by, bluffed_card) = match claim {
+					AuditEvent::Claim { by, cards, .. } => (by, cards.first().copied()),
+					AuditEvent::Disclaim { by, card, .. } => (by, Some(*card)),
+					AuditEvent::Shuffle { .. } | AuditEvent::Exchange { .. } => continue,
+				};
+				let bluffed_card = bluffed_card.filter(|_| !truthful && !is_counter);
+
+				let stats = self.stats_of(by);
+				match (is_counter, truthful) {
+					(true, true) => stats.real_counters += 1,
+					(true, false) => stats.bluffed_counters += 1,
+					(false, _) => {
+						if let Some(card) = bluffed_card {
+							add_to(&mut stats.bluffs, card);
+						}
+					},
+				}
+				open_claim = Some(OpenClaim {
+					by: by.clone(),
+					bluffed_card,
+					truthful,
+				});
+				continue;
+			}
+
+			match event {
+				History::ChallengeAssassin { by, target }
+				| History::ChallengeAmbassador { by, target }
+				| History::ChallengeCaptain { by, target }
+				| History::ChallengeDuke { by, target }
+				| History::ChallengeEmbezzlement { by, target }
+				| History::ChallengeInquisitor { by, target }
+				| History::ChallengeContessa { by, target }
+				| History::CounterChallengeContessa { by, target }
+				| History::CounterChallengeDuke { by, target }
+				| History::CounterChallengeCaptainAmbassedor { by, target }
+				| History::CounterChallengeAssassin { by, target } => {
+					let Some(claim) =
+						open_claim.take().filter(|claim| claim.by == *target)
+					else {
+						continue;
+					};
+					let challenger = self.stats_of(by);
+					challenger.challenges += 1;
+					if claim.truthful {
+						challenger.truthful_challenges += 1;
+					}
+					if let Some(card) = claim.bluffed_card {
+						add_to(&mut self.stats_of(target).challenged_bluffs, card);
+					}
+				},
+				History::ActionAssassination { .. }
+				| History::ActionCoup { .. }
+				| History::ActionForeignAid { .. }
+				| History::ActionSwapping { .. }
+				| History::ActionIncome { .. }
+				| History::ActionStealing { .. }
+				| History::ActionTax { .. }
+				| History::ActionConversion { .. }
+				| History::ActionEmbezzlement { .. }
+				| History::ActionExamine { .. }
+				| History::ActionExamineSwap { .. }
+				| History::Draft { .. }
+				| History::CounterAssassination { .. }
+				| History::CounterForeignAid { .. }
+				| History::CounterStealing { .. }
+				| History::CardLoss { .. } => {},
+			}
+		}
+	}
+
+	/// The stats of a bot or `None` if it didn't play.
+	pub fn get(&self, name: &str) -> Option<&BluffStats> {
+		self.bots.iter().find(|stats| stats.name == name)
+	}
+
+	fn stats_of(&mut self, name: &str) -> &mut BluffStats {
+		match self.bots.iter().position(|stats| stats.name == name) {
+			Some(index) => &mut self.bots[index],
+			None => {
+				self.bots.push(BluffStats::new(name.to_string()));
+				self.bots.last_mut().unwrap()
+			},
+		}
+	}
+}
+
+impl fmt::Display for BluffReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, " 🃏 Bluffs")?;
+		for stats in self.bots.iter() {
+			writeln!(
+				f,
+				"\x1b[90m {:>8}\x1b[39m bluffs \x1b[90m{:>8}\x1b[39m challenged  \x1b[33m{}\x1b[39m",
+				stats.total_bluffs(),
+				stats.total_bluffs_challenged(),
+				stats.name
+			)?;
+			for (card, bluffs) in stats.bluffs.iter() {
+				writeln!(
+					f,
+					"\x1b[90m {:>8}\x1b[39m        \x1b[90m{:>8}\x1b[39m             {:?}",
+					bluffs,
+					stats.bluffs_challenged(*card),
+					card
+				)?;
+			}
+			writeln!(
+				f,
+				"          {} of {} challenges were against the truth, {} real and {} bluffed counters",
+				stats.truthful_challenges,
+				stats.challenges,
+				stats.real_counters,
+				stats.bluffed_counters
+			)?;
+		}
+		Ok(())
+	}
+}
+
+fn count_of(counts: &[(Card, usize)], card: Card) -> usize {
+	counts
+		.iter()
+		.find(|(counted_card, _)| *counted_card == card)
+		.map_or(0, |(_, count)| *count)
+}
+
+fn add_to(counts: &mut Vec<(Card, usize)>, card: Card) {
+	match counts.iter_mut().find(|(counted_card, _)| *counted_card == card) {
+		Some((_, count)) => *count += 1,
+		None => counts.push((card, 1)),
+	}
+}
@@ -14,7 +14,15 @@
 //! }
 //! ```
 
-use crate::{Action, Card, History, Score};
+use std::cell::RefCell;
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+use crate::{
+	config::{Config, Handicap},
+	role::RoleSet,
+	Action, Allegiance, Card, History, Score,
+};
 
 /// A bot struct can be used to implement the [BotInterface] trait
 #[derive(Debug, Clone, Copy)]
@@ -29,12 +37,89 @@ pub struct OtherBot {
 	pub coins: u8,
 	/// The amount of [Card] this bot still have
 	pub cards: u8,
+	/// The faction this bot belongs to in the Reformation expansion
+	pub allegiance: Option<Allegiance>,
+	/// The team this bot belongs to in team play
+	pub team: Option<usize>,
+	/// The starting conditions of this bot if they differ from the rules
+	pub handicap: Option<Handicap>,
+}
+
+/// The action of the current turn that is still being challenged or countered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingTurn {
+	/// The name of the bot who took the action
+	pub actor: String,
+	/// The action that was taken
+	pub action: Action,
+	/// The name of the bot the action is targeting if any
+	pub target: Option<String>,
+	/// The card the actor claims to have (or, when embezzling, claims not to
+	/// have) to take this action
+	pub claimed_card: Option<Card>,
+	/// The name of the bot who countered the action if any
+	pub counterer: Option<String>,
+	/// The challenges already resolved in this turn in the order they happened
+	pub challenges: Vec<PendingChallenge>,
+}
+
+/// A challenge that was already resolved during the [PendingTurn].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingChallenge {
+	/// The name of the bot who challenged
+	pub by: String,
+	/// The name of the bot who was challenged
+	pub target: String,
+	/// Whether the challenged bot was caught bluffing
+	pub successful: bool,
+}
+
+/// A card only you saw, for example a card you put back into the deck.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownCard {
+	/// The card you saw
+	pub card: Card,
+	/// How you saw this card
+	pub source: KnownCardSource,
+	/// The move of the game you saw this card in
+	pub turn: usize,
+}
+
+/// How you came to know a [KnownCard].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KnownCardSource {
+	/// You put this card back into the deck after an exchange, a draft or
+	/// because of your handicap
+	Returned,
+	/// You set this card aside when choosing your starting card in the
+	/// two-player variant and it's out of the game
+	SetAside,
+	/// You revealed this card to win a challenge or because the Inquisitor
+	/// forced you to and shuffled it into the deck
+	Shuffled,
+	/// You examined this card of another bot with the Inquisitor
+	Examined {
+		/// The name of the bot who showed you this card
+		owner: String,
+	},
 }
 
 /// The context struct is what is passed into each of the [BotInterface] methods
 /// as arguments so the bot knows the context of the current move.
 /// This is where your game state is stored including your current cards and
 /// coins but also what other bots are still in the game, the discard pile etc.
+///
+/// To call your bot outside of a game, e.g. in its tests, start from
+/// [Context::default] and set the fields you need:
+///
+/// ```rust
+/// use coup::{bot::Context, Card};
+///
+/// let mut context = Context::default();
+/// context.name = String::from("Kate");
+/// context.cards = vec![Card::Duke, Card::Captain];
+/// context.coins = 2;
+/// ```
 #[derive(Debug, Clone, PartialEq)]
 pub struct Context {
 	/// Your bots name after it was deduped by the engine as identifier
@@ -51,6 +136,141 @@ pub struct Context {
 	pub history: Vec<History>,
 	/// The current score of the game
 	pub score: Score,
+	/// The coins in the treasury reserve of the Reformation expansion
+	pub treasury_reserve: u8,
+	/// Whether you are allowed to target your teammates in team play
+	pub friendly_fire: bool,
+	/// The roles of the deck and which actions and counters they allow
+	pub roles: RoleSet,
+	/// The names of all bots seated at this game in seat order, including bots
+	/// that were already eliminated.
+	/// Challenges and counters are asked in this order starting after the bot
+	/// who played.
+	pub seats: Vec<String>,
+	/// The index in [Context::seats] of the bot whose turn it is
+	pub turn: usize,
+	/// The amount of moves played so far in this game
+	pub moves: usize,
+	/// The amount of moves after which the game is stopped as a stalemate
+	pub max_moves: usize,
+	/// The amount of cards left in the deck
+	pub deck_size: usize,
+	/// The name of the bot whose turn it is
+	pub acting_bot: String,
+	/// The action of the current turn while it is being challenged or countered
+	pub pending_turn: Option<PendingTurn>,
+	/// The cards only you saw in this game in the order you saw them
+	pub known_cards: Vec<KnownCard>,
+	/// The number the engine drew from the random stream it keeps for your bot
+	/// for this callback.
+	/// Use [Context::rng] for your random decisions so seeded games can be
+	/// played again exactly.
+	pub seed: u64,
+	pub(crate) rng: SeedRng,
+}
+
+impl Default for Context {
+	/// An empty context of the base game without any bots
+	fn default() -> Self {
+		Self {
+			name: String::new(),
+			cards: vec![],
+			coins: 0,
+			playing_bots: vec![],
+			discard_pile: vec![],
+			history: vec![],
+			score: vec![],
+			treasury_reserve: 0,
+			friendly_fire: false,
+			roles: RoleSet::classic(),
+			seats: vec![],
+			turn: 0,
+			moves: 0,
+			max_moves: Config::default().max_moves,
+			deck_size: 0,
+			acting_bot: String::new(),
+			pending_turn: None,
+			known_cards: vec![],
+			seed: 0,
+			rng: SeedRng::default(),
+		}
+	}
+}
+
+// The random stream of a context, started from Context::seed on first use.
+// It doesn't take part in comparing contexts as two contexts with the same seed
+// are the same no matter how much of the stream was used.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SeedRng(RefCell<Option<StdRng>>);
+
+impl PartialEq for SeedRng {
+	fn eq(&self, _other: &Self) -> bool {
+		true
+	}
+}
+
+impl Context {
+	/// The random number generator of this callback seeded from
+	/// [Context::seed].
+	/// All generators of a context share the same stream so calling it twice
+	/// gives you different numbers.
+	pub fn rng(&self) -> ContextRng<'_> {
+		ContextRng { context: self }
+	}
+
+	/// A list of all bots you can target with an action.
+	/// This excludes yourself, your teammates in team play without friendly fire
+	/// and, in the Reformation expansion, all bots of your own faction as long as
+	/// a bot of another faction is still playing.
+	pub fn targets(&self) -> Vec<&OtherBot> {
+		let me = self.playing_bots.iter().find(|bot| bot.name == self.name);
+		let allegiance = me.and_then(|bot| bot.allegiance);
+		let team = me.and_then(|bot| bot.team);
+		let factions_divided =
+			self.playing_bots.iter().any(|bot| bot.allegiance != allegiance);
+
+		self
+			.playing_bots
+			.iter()
+			.filter(|bot| bot.name != self.name)
+			.filter(|bot| {
+				allegiance.is_none()
+					|| !factions_divided
+					|| bot.allegiance != allegiance
+			})
+			.filter(|bot| self.friendly_fire || team.is_none() || bot.team != team)
+			.collect()
+	}
+}
+
+/// The random stream of a single callback handed out by [Context::rng].
+pub struct ContextRng<'a> {
+	context: &'a Context,
+}
+
+impl ContextRng<'_> {
+	fn draw<T>(&mut self, draw: impl FnOnce(&mut StdRng) -> T) -> T {
+		let mut rng = self.context.rng.0.borrow_mut();
+		draw(rng.get_or_insert_with(|| StdRng::seed_from_u64(self.context.seed)))
+	}
+}
+
+impl RngCore for ContextRng<'_> {
+	fn next_u32(&mut self) -> u32 {
+		self.draw(|rng| rng.next_u32())
+	}
+
+	fn next_u64(&mut self) -> u64 {
+		self.draw(|rng| rng.next_u64())
+	}
+
+	fn fill_bytes(&mut self, dest: &mut [u8]) {
+		self.draw(|rng| rng.fill_bytes(dest))
+	}
+
+	fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+		self.draw(|rng| rng.try_fill_bytes(dest))
+	}
 }
 
 /// The BotInterface trait is what drives your bot.
@@ -62,6 +282,28 @@ pub trait BotInterface {
 	/// The name might get a number appended if there is another bot with the same name.
 	fn get_name(&self) -> String;
 
+	/// Called at the start of a game in the two-player variant to choose your
+	/// first card from a hand of one of each role.
+	/// Your second card is dealt at random from the deck.
+	///
+	/// The static implementation chooses the first card it is offered.
+	fn on_choose_starting_card(
+		&self,
+		cards: Vec<Card>,
+		_context: &Context,
+	) -> Card {
+		cards[0]
+	}
+
+	/// Called at the start of a game in draft mode to choose your two starting
+	/// cards from the cards you are shown.
+	/// The cards you don't choose are shuffled back into the deck.
+	///
+	/// The static implementation chooses the first two cards it is shown.
+	fn on_draft(&self, cards: Vec<Card>, _context: &Context) -> [Card; 2] {
+		[cards[0], cards[1]]
+	}
+
 	/// Called when it's your turn to decide what to do.
 	///
 	/// The static implementation always plays [Action::Income].
@@ -70,18 +312,23 @@ pub trait BotInterface {
 	}
 
 	/// Called when you have equal to or more than 10 coins and must coup.
+	/// It's not called while you can't target anyone, see [Context::targets].
 	/// You can use this method internally as well when you decide to coup on
 	/// your own.
 	///
-	/// The static implementation coups the first bot it finds that isn't itself.
+	/// The static implementation coups the first bot it finds that it can
+	/// target or any other bot if it can't target anyone.
 	fn on_auto_coup(&self, context: &Context) -> String {
-		context
-			.playing_bots
-			.iter()
-			.find(|bot| bot.name != context.name)
-			.unwrap()
-			.name
-			.clone()
+		match context.targets().first() {
+			Some(target) => target.name.clone(),
+			None => context
+				.playing_bots
+				.iter()
+				.find(|bot| bot.name != context.name)
+				.unwrap()
+				.name
+				.clone(),
+		}
 	}
 
 	/// Called when another bot played an action and everyone gets to decide
@@ -89,6 +336,7 @@ pub trait BotInterface {
 	///
 	/// Called for:
 	/// - [Action::Assassination]
+	/// - [Action::Examine]
 	/// - [Action::Swapping]
 	/// - [Action::Stealing]
 	/// - [Action::Tax]
@@ -103,6 +351,15 @@ pub trait BotInterface {
 		false
 	}
 
+	/// Called when another bot played [Action::Embezzlement] in the Reformation
+	/// expansion and everyone gets to decide whether they want to challenge the
+	/// claim that this bot doesn't have the [Card::Duke].
+	///
+	/// The static implementation never challenges.
+	fn on_challenge_embezzlement(&self, _by: String, _context: &Context) -> bool {
+		false
+	}
+
 	/// Called when someone played something that can be countered with a card
 	/// you may have.
 	///
@@ -153,6 +410,38 @@ pub trait BotInterface {
 		new_cards
 	}
 
+	/// Called when you played your inquisitor and now need to decide which card
+	/// you want to keep.
+	/// Return the card you don't want anymore. It will be shuffled back into the
+	/// deck.
+	///
+	/// The static implementation gives back the card it got from the deck.
+	fn on_swapping_card(&self, new_card: Card, _context: &Context) -> Card {
+		new_card
+	}
+
+	/// Called when another bot examines you with its inquisitor and you need to
+	/// decide which of your cards you want to show it.
+	///
+	/// The static implementation shows the first card it finds.
+	fn on_examine(&self, _by: String, context: &Context) -> Card {
+		context.cards[0]
+	}
+
+	/// Called when you examined another bot with your inquisitor and now need to
+	/// decide whether that bot has to swap the card it showed you with a new card
+	/// from the deck.
+	///
+	/// The static implementation never forces a swap.
+	fn on_examined_card(
+		&self,
+		_card: Card,
+		_target: String,
+		_context: &Context,
+	) -> bool {
+		false
+	}
+
 	/// Called when you lost a card and now must decide which one you want to lose.
 	///
 	/// The static implementation discards the first card it finds.
@@ -1,6 +1,7 @@
 //! An honest bot implementation for you to use to test your own bot with.
 
 use crate::{
+	belief::Belief,
 	bot::{BotInterface, Context},
 	Action, Card,
 };
@@ -16,39 +17,55 @@ impl BotInterface for HonestBot {
 		String::from("HonestBot")
 	}
 
+	/// Drafts the cards it acts on first
+	fn on_draft(&self, mut cards: Vec<Card>, _context: &Context) -> [Card; 2] {
+		let preference =
+			[Card::Assassin, Card::Captain, Card::Duke, Card::Contessa];
+		cards.sort_by_key(|card| {
+			preference.iter().position(|c| c == card).unwrap_or(preference.len())
+		});
+		[cards[0], cards[1]]
+	}
+
 	/// Acts on cards it has and falls back to [Action::Income].
 	/// Never plays [Action::ForeignAid] or [Action::Swapping].
 	fn on_turn(&self, context: &Context) -> Action {
-		let target = context
-			.playing_bots
-			.iter()
-			.filter(|bot| bot.name != context.name)
-			.min_by_key(|bot| bot.cards)
-			.unwrap();
-
-		if context.cards.contains(&Card::Assassin) && context.coins >= 3 {
-			Action::Assassination(target.name.clone())
-		} else if context.cards.contains(&Card::Captain) {
-			Action::Stealing(target.name.clone())
-		} else if context.cards.contains(&Card::Duke) {
+		let target = context.targets().into_iter().min_by_key(|bot| bot.cards);
+
+		let assassination = target
+			.map(|bot| Action::Assassination(bot.name.clone()))
+			.filter(|action| {
+				has_role_for(action, context)
+					&& context.coins >= context.roles.cost(action)
+			});
+		let stealing = target
+			.map(|bot| Action::Stealing(bot.name.clone()))
+			.filter(|action| has_role_for(action, context));
+		if let Some(action) = assassination.or(stealing) {
+			action
+		} else if has_role_for(&Action::Tax, context) {
 			Action::Tax
 		} else {
 			Action::Income
 		}
 	}
 
-	/// Looks for the bot with the least cards
+	/// Looks for the bot with the least cards or any other bot if it can't
+	/// target anyone
 	fn on_auto_coup(&self, context: &Context) -> String {
-		let target = context
-			.playing_bots
-			.iter()
-			.filter(|bot| bot.name != context.name)
-			.min_by_key(|bot| bot.cards)
-			.unwrap();
-		target.name.clone()
+		match context.targets().into_iter().min_by_key(|bot| bot.cards) {
+			Some(target) => target.name.clone(),
+			None => context
+				.playing_bots
+				.iter()
+				.find(|bot| bot.name != context.name)
+				.unwrap()
+				.name
+				.clone(),
+		}
 	}
 
-	/// Challenges only if it can see all three cards associated with the current
+	/// Challenges only if it can see all cards associated with the current
 	/// action in either the discard pile or its own hand.
 	fn on_challenge_action_round(
 		&self,
@@ -56,31 +73,9 @@ impl BotInterface for HonestBot {
 		_by: String,
 		context: &Context,
 	) -> bool {
-		let mut all_visible_cards = context.cards.clone();
-		all_visible_cards.extend(context.discard_pile.clone());
-
-		match action {
-			Action::Assassination(_) => {
-				all_visible_cards.iter().filter(|card| **card == Card::Assassin).count()
-					== 3
-			},
-			Action::Swapping => {
-				all_visible_cards
-					.iter()
-					.filter(|card| **card == Card::Ambassador)
-					.count() == 3
-			},
-			Action::Stealing(_) => {
-				all_visible_cards.iter().filter(|card| **card == Card::Captain).count()
-					== 3
-			},
-			Action::Tax => {
-				all_visible_cards.iter().filter(|card| **card == Card::Duke).count()
-					== 3
-			},
-			Action::Coup(_) | Action::ForeignAid | Action::Income => {
-				unreachable!("Can't challenge couping or Income")
-			},
+		match context.roles.claimed_card(action) {
+			Some(card) => all_visible(card, context),
+			None => false,
 		}
 	}
 
@@ -91,46 +86,28 @@ impl BotInterface for HonestBot {
 		_by: String,
 		context: &Context,
 	) -> bool {
-		match action {
-			Action::Assassination(_) => context.cards.contains(&Card::Contessa),
-			Action::ForeignAid => context.cards.contains(&Card::Duke),
-			Action::Stealing(_) => {
-				context.cards.contains(&Card::Captain)
-					|| context.cards.contains(&Card::Ambassador)
-			},
-			Action::Coup(_) | Action::Swapping | Action::Income | Action::Tax => {
-				unreachable!("Can't challenge couping or Income")
-			},
-		}
+		context
+			.roles
+			.blockers(action)
+			.iter()
+			.any(|card| context.cards.contains(card))
 	}
 
-	/// Counter-challenges only if it can see all three cards associated with the
+	/// Counter-challenges only if it can see all cards associated with the
 	/// current action in either the discard pile or its own hand.
+	/// Foreign aid is counter-challenged whenever it holds a card blocking it.
 	fn on_challenge_counter_round(
 		&self,
 		action: &Action,
 		_by: String,
 		context: &Context,
 	) -> bool {
-		let mut all_visible_cards = context.cards.clone();
-		all_visible_cards.extend(context.discard_pile.clone());
-
-		match action {
-			Action::Assassination(_) => {
-				all_visible_cards.iter().filter(|card| **card == Card::Contessa).count()
-					== 3
-			},
-			Action::ForeignAid => context.cards.contains(&Card::Duke),
-			Action::Stealing(_) => {
-				all_visible_cards.iter().filter(|card| **card == Card::Captain).count()
-					== 3 && all_visible_cards
-					.iter()
-					.filter(|card| **card == Card::Ambassador)
-					.count() == 3
-			},
-			Action::Coup(_) | Action::Income | Action::Swapping | Action::Tax => {
-				unreachable!("Can't challenge couping or Income")
-			},
+		let blockers = context.roles.blockers(action);
+		if *action == Action::ForeignAid {
+			blockers.iter().any(|card| context.cards.contains(card))
+		} else {
+			!blockers.is_empty()
+				&& blockers.iter().all(|card| all_visible(*card, context))
 		}
 	}
 
@@ -151,8 +128,46 @@ impl BotInterface for HonestBot {
 		[discard_cards[0], discard_cards[1]]
 	}
 
+	/// Swaps a duplicate card
+	fn on_swapping_card(&self, new_card: Card, context: &Context) -> Card {
+		if context.cards.len() == 2 && context.cards[0] == context.cards[1] {
+			context.cards[0]
+		} else {
+			new_card
+		}
+	}
+
+	/// Forces a swap when the examined card could block its own actions
+	fn on_examined_card(
+		&self,
+		card: Card,
+		_target: String,
+		context: &Context,
+	) -> bool {
+		match card {
+			Card::Contessa => context.cards.contains(&Card::Assassin),
+			Card::Captain | Card::Ambassador | Card::Inquisitor => {
+				context.cards.contains(&Card::Captain)
+			},
+			Card::Assassin | Card::Duke => false,
+		}
+	}
+
 	/// Takes the first card to discard
 	fn on_card_loss(&self, context: &Context) -> Card {
 		context.cards.clone().pop().unwrap()
 	}
 }
+
+// Whether the bot holds the card of the role that allows this action
+fn has_role_for(action: &Action, context: &Context) -> bool {
+	context
+		.roles
+		.claimed_card(action)
+		.is_some_and(|card| context.cards.contains(&card))
+}
+
+// Whether all copies of a card are in its hand or the discard pile
+fn all_visible(card: Card, context: &Context) -> bool {
+	Belief::new(context).unseen_count(card) == 0
+}
@@ -1,6 +1,6 @@
 //! A random bot implementation for you to use to test your own bot with.
 
-use rand::{seq::SliceRandom, thread_rng};
+use rand::seq::SliceRandom;
 
 use crate::{
 	bot::{BotInterface, Context, OtherBot},
@@ -18,15 +18,27 @@ impl BotInterface for RandomBot {
 		String::from("RandomBot")
 	}
 
+	/// Randomizes what starting card it chooses
+	fn on_choose_starting_card(
+		&self,
+		mut cards: Vec<Card>,
+		context: &Context,
+	) -> Card {
+		cards.shuffle(&mut context.rng());
+		cards[0]
+	}
+
+	/// Randomizes what starting cards it drafts
+	fn on_draft(&self, mut cards: Vec<Card>, context: &Context) -> [Card; 2] {
+		cards.shuffle(&mut context.rng());
+		[cards[0], cards[1]]
+	}
+
 	/// Randomizes all possible [Action]
 	fn on_turn(&self, context: &Context) -> Action {
-		let mut targets = context.playing_bots.clone();
-		targets = targets
-			.iter()
-			.filter(|bot| bot.name != context.name)
-			.cloned()
-			.collect::<Vec<OtherBot>>();
-		targets.shuffle(&mut thread_rng());
+		let mut rng = context.rng();
+		let mut targets = random_targets(context);
+		targets.shuffle(&mut rng);
 
 		let mut actions = [
 			Action::Assassination(targets[0].name.clone()),
@@ -37,19 +49,14 @@ impl BotInterface for RandomBot {
 			Action::Stealing(targets[0].name.clone()),
 			Action::Tax,
 		];
-		actions.shuffle(&mut thread_rng());
+		actions.shuffle(&mut rng);
 		actions[0].clone()
 	}
 
 	/// Randomizes who it coups
 	fn on_auto_coup(&self, context: &Context) -> String {
-		let mut targets = context.playing_bots.clone();
-		targets = targets
-			.iter()
-			.filter(|bot| bot.name != context.name)
-			.cloned()
-			.collect::<Vec<OtherBot>>();
-		targets.shuffle(&mut thread_rng());
+		let mut targets = random_targets(context);
+		targets.shuffle(&mut context.rng());
 		targets[0].name.clone()
 	}
 
@@ -58,10 +65,10 @@ impl BotInterface for RandomBot {
 		&self,
 		_action: &Action,
 		_by: String,
-		_context: &Context,
+		context: &Context,
 	) -> bool {
 		let mut challange = [true, false];
-		challange.shuffle(&mut thread_rng());
+		challange.shuffle(&mut context.rng());
 		challange[0]
 	}
 
@@ -70,10 +77,10 @@ impl BotInterface for RandomBot {
 		&self,
 		_action: &Action,
 		_by: String,
-		_context: &Context,
+		context: &Context,
 	) -> bool {
 		let mut counter = [true, false];
-		counter.shuffle(&mut thread_rng());
+		counter.shuffle(&mut context.rng());
 		counter[0]
 	}
 
@@ -82,10 +89,10 @@ impl BotInterface for RandomBot {
 		&self,
 		_action: &Action,
 		_by: String,
-		_context: &Context,
+		context: &Context,
 	) -> bool {
 		let mut challange = [true, false];
-		challange.shuffle(&mut thread_rng());
+		challange.shuffle(&mut context.rng());
 		challange[0]
 	}
 
@@ -97,15 +104,53 @@ impl BotInterface for RandomBot {
 	) -> [Card; 2] {
 		let mut all_visible_cards = context.cards.clone();
 		all_visible_cards.extend(new_cards);
-		all_visible_cards.shuffle(&mut thread_rng());
+		all_visible_cards.shuffle(&mut context.rng());
 
 		[all_visible_cards[0], all_visible_cards[1]]
 	}
 
+	/// Randomizes what card it swaps
+	fn on_swapping_card(&self, new_card: Card, context: &Context) -> Card {
+		let mut all_visible_cards = context.cards.clone();
+		all_visible_cards.push(new_card);
+		all_visible_cards.shuffle(&mut context.rng());
+
+		all_visible_cards[0]
+	}
+
+	/// Randomizes what card it shows
+	fn on_examine(&self, _by: String, context: &Context) -> Card {
+		let mut cards = context.cards.clone();
+		cards.shuffle(&mut context.rng());
+		cards[0]
+	}
+
+	/// Randomizes if it forces a swap or not
+	fn on_examined_card(
+		&self,
+		_card: Card,
+		_target: String,
+		context: &Context,
+	) -> bool {
+		let mut swap = [true, false];
+		swap.shuffle(&mut context.rng());
+		swap[0]
+	}
+
 	/// Randomizes what card it discards
 	fn on_card_loss(&self, context: &Context) -> Card {
 		let mut cards = context.cards.clone();
-		cards.shuffle(&mut thread_rng());
+		cards.shuffle(&mut context.rng());
 		cards[0]
 	}
 }
+
+// The bots it may target, or any other bot if it can't target anyone
+fn random_targets(context: &Context) -> Vec<&OtherBot> {
+	let targets = context.targets();
+	if targets.is_empty() {
+		context.playing_bots.iter().filter(|bot| bot.name != context.name).collect()
+	} else {
+		targets
+	}
+}
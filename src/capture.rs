@@ -0,0 +1,146 @@
+//! [Capture] odd games of a [crate::Coup::looping] run to files so they can be
+//! looked at and replayed later. Captured files include the [crate::audit]
+//! trail of the game.
+//!
+//! ```rust
+//! use coup::{bots::RandomBot, capture::Capture, Coup};
+//!
+//! let mut coup_game = Coup::new(vec![Box::new(RandomBot), Box::new(RandomBot)]);
+//! let folder = std::env::temp_dir().join("coup-captures");
+//!
+//! // Also capture all games that took more than 20 moves
+//! coup_game.set_capture(
+//!     Capture::new(&folder).with_predicate(|result, _history| result.moves > 20),
+//! );
+//!
+//! coup_game.looping(5);
+//! ```
+
+use std::{
+	fs, io,
+	path::{Path, PathBuf},
+};
+
+use crate::{
+	audit::AuditEntry, replay::Replay, result::GameResult, Card, History,
+};
+
+/// A user predicate to capture a game by its result and history.
+pub type CapturePredicate = Box<dyn Fn(&GameResult, &[History]) -> bool>;
+
+/// Where and which games of a [crate::Coup::looping] run are written to files.
+///
+/// Games that are stopped at [crate::config::Config::max_moves], in which a
+/// bot was penalized or that panicked are always captured.
+pub struct Capture {
+	/// The folder the captured games are written to
+	pub folder: PathBuf,
+	/// Capture all games this predicate returns true for as well
+	pub predicate: Option<CapturePredicate>,
+}
+
+/// Why a game was captured.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaptureReason {
+	/// The game was stopped at [crate::config::Config::max_moves]
+	MaxMoves,
+	/// A bot was penalized during the game
+	Penalty,
+	/// The game panicked with this message
+	Panic(String),
+	/// The predicate of the [Capture] returned true
+	Predicate,
+}
+
+impl CaptureReason {
+	fn slug(&self) -> &'static str {
+		match self {
+			CaptureReason::MaxMoves => "max-moves",
+			CaptureReason::Penalty => "penalty",
+			CaptureReason::Panic(_) => "panic",
+			CaptureReason::Predicate => "predicate",
+		}
+	}
+}
+
+impl Capture {
+	/// Capture odd games into this folder.
+	/// The folder is created if it doesn't exist yet.
+	pub fn new(folder: impl AsRef<Path>) -> Self {
+		Self {
+			folder: folder.as_ref().to_path_buf(),
+			predicate: None,
+		}
+	}
+
+	/// Capture all games this predicate returns true for as well.
+	pub fn with_predicate(
+		mut self,
+		predicate: impl Fn(&GameResult, &[History]) -> bool + 'static,
+	) -> Self {
+		self.predicate = Some(Box::new(predicate));
+		self
+	}
+
+	// Write a game as a replay file with everything else we know about it as
+	// comments on top
+	pub(crate) fn write(
+		&self,
+		round: u64,
+		reason: &CaptureReason,
+		replay: &Replay,
+		starting_hands: &[(String, Vec<Card>)],
+		transcript: &[String],
+		audit: &[AuditEntry],
+	) -> io::Result<PathBuf> {
+		fs::create_dir_all(&self.folder)?;
+		let path = self.folder.join(format!(
+			"game-{:08}-{}.replay",
+			round + 1,
+			reason.slug()
+		));
+
+		let mut lines = vec![
+			format!("# round {}", round + 1),
+			format!("# reason {:?}", reason),
+		];
+		for (name, cards) in starting_hands.iter() {
+			lines.push(format!("# starting hand {} {:?}", name, cards));
+		}
+		for line in transcript.iter().flat_map(|message| message.lines()) {
+			lines.push(format!("#{}", strip_colors(line)));
+		}
+		for entry in audit.iter() {
+			let verdict = match entry.event.truthful() {
+				Some(true) => " truthful",
+				Some(false) => " bluff",
+				None => "",
+			};
+			lines.push(format!(
+				"# audit {} {:?}{}",
+				entry.history, entry.event, verdict
+			));
+		}
+
+		fs::write(&path, format!("{}\n{}", lines.join("\n"), replay))?;
+		Ok(path)
+	}
+}
+
+// Removes the terminal escape codes the engine colors its log with
+fn strip_colors(line: &str) -> String {
+	let mut stripped = String::new();
+	let mut chars = line.chars();
+	while let Some(char) = chars.next() {
+		if char == '\x1b' {
+			for code in chars.by_ref() {
+				if code.is_ascii_alphabetic() {
+					break;
+				}
+			}
+		} else {
+			stripped.push(char);
+		}
+	}
+	stripped
+}
@@ -0,0 +1,157 @@
+//! The [Config] struct that changes the rules the engine plays by.
+//!
+//! ```rust
+//! use coup::{bots::StaticBot, config::Config, Coup};
+//!
+//! let mut coup_game = Coup::new_with_config(
+//!     vec![Box::new(StaticBot), Box::new(StaticBot)],
+//!     Config {
+//!         reformation: true,
+//!         ..Config::default()
+//!     },
+//! );
+//!
+//! coup_game.play();
+//! ```
+
+use crate::{role::RoleSet, Deck};
+
+/// The configuration of a Coup game.
+/// The default configuration plays the base game without any expansions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+	/// Play with the factions of the Reformation expansion.
+	/// Each bot gets a [crate::Allegiance] and the actions
+	/// [crate::Action::Conversion] and [crate::Action::Embezzlement] become
+	/// available.
+	pub reformation: bool,
+	/// The role set the deck is made of.
+	/// Use [crate::Deck::Inquisitor] to replace the [crate::Card::Ambassador] with
+	/// the [crate::Card::Inquisitor].
+	pub deck: Deck,
+	/// A custom [RoleSet] replacing the roles of the [Config::deck].
+	pub roles: Option<RoleSet>,
+	/// Play the official two-player variant.
+	/// Each game seats two bots who each choose their first card from a hand of
+	/// one of each role and the starting bot only gets one coin.
+	/// The cards a bot doesn't choose are set aside for the rest of the game so
+	/// the deck only keeps the cards that weren't offered.
+	pub two_player: bool,
+	/// Let each bot in seat order draft its two starting cards from the given
+	/// amount of cards (between 2 and 5) shown from the deck.
+	/// Ignored in the two-player variant.
+	pub draft: Option<usize>,
+	/// The maximum amount of bots seated at each game between 2 and 10.
+	/// Tables with more than 6 bots get a bigger deck.
+	pub max_players: usize,
+	/// The amount of moves after which a game is stopped as a stalemate.
+	pub max_moves: usize,
+	/// Stop a game as a stalemate once the same public state (turn, coins, cards
+	/// and allegiance of each bot, discard pile and treasury reserve) came up
+	/// this many times.
+	/// Off by default, `Some(3)` is a good start.
+	pub stalemate_repetitions: Option<usize>,
+	/// Stop a game as a stalemate if coins and cards of all bots are the same as
+	/// they were this many rounds ago.
+	/// Off by default, `Some(20)` is a good start.
+	pub stalemate_rounds: Option<usize>,
+	/// The rules that decide a game stopped in a stalemate, applied in order
+	/// until only one bot (or team) is left.
+	/// Without tie-breakers all remaining bots share the win.
+	pub tie_breakers: Vec<TieBreaker>,
+	/// Play in teams by assigning a team to each bot in the order they were
+	/// passed into [crate::Coup::new_with_config].
+	/// A team wins once only its members are left and all members of a team
+	/// share their score.
+	pub teams: Option<Vec<usize>>,
+	/// Allow teammates to target each other in team play.
+	pub friendly_fire: bool,
+	/// Give each bot its own starting conditions in the order they were passed
+	/// into [crate::Coup::new_with_config].
+	pub handicaps: Option<Vec<Handicap>>,
+	/// Seed the random numbers of the engine and of each bot so the same bots
+	/// play the same games move by move each time.
+	/// Without a seed each run is different.
+	pub seed: Option<u64>,
+}
+
+impl Default for Config {
+	fn default() -> Self {
+		Self {
+			reformation: false,
+			deck: Deck::Classic,
+			roles: None,
+			two_player: false,
+			draft: None,
+			max_players: 6,
+			max_moves: 1000,
+			stalemate_repetitions: None,
+			stalemate_rounds: None,
+			tie_breakers: vec![],
+			teams: None,
+			friendly_fire: false,
+			handicaps: None,
+			seed: None,
+		}
+	}
+}
+
+impl Config {
+	// Why the engine can't play this config with this many bots if it can't
+	pub(crate) fn check(&self, bots: usize) -> Result<(), String> {
+		if !(2..=10).contains(&self.max_players) {
+			return Err(String::from(
+				"The engine supports between 2 and 10 players per game",
+			));
+		}
+		if self.draft.is_some_and(|draft| !(2..=5).contains(&draft)) {
+			return Err(String::from(
+				"Bots can be shown between 2 and 5 cards to draft from",
+			));
+		}
+		if let Some(roles) = &self.roles {
+			if !(1..=2).contains(&roles.exchange_cards()) {
+				return Err(String::from("Roles can only exchange 1 or 2 cards"));
+			}
+		}
+		if self.teams.as_ref().is_some_and(|teams| teams.len() != bots) {
+			return Err(String::from("Each bot needs to be assigned to a team"));
+		}
+		if let Some(handicaps) = &self.handicaps {
+			if handicaps.len() != bots {
+				return Err(String::from("Each bot needs to be given a handicap"));
+			}
+			if !handicaps.iter().all(|handicap| (1..=2).contains(&handicap.cards)) {
+				return Err(String::from("Bots can only start with 1 or 2 cards"));
+			}
+		}
+		Ok(())
+	}
+}
+
+/// A rule to decide a game stopped in a stalemate.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TieBreaker {
+	/// The bots with the most cards left win.
+	MostInfluence,
+	/// The bots with the most coins win.
+	MostCoins,
+	/// All remaining bots share the win.
+	Shared,
+}
+
+/// The starting conditions of a bot.
+/// The default are the starting conditions of the rules.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Handicap {
+	/// The coins this bot starts the game with
+	pub coins: u8,
+	/// The amount of cards this bot starts the game with, either 1 or 2
+	pub cards: usize,
+}
+
+impl Default for Handicap {
+	fn default() -> Self {
+		Self { coins: 2, cards: 2 }
+	}
+}
@@ -26,13 +26,46 @@
 extern crate cfonts;
 
 use cfonts::{render, Colors, Options};
-use rand::{seq::SliceRandom, thread_rng};
-use std::fmt;
-
+use rand::{rngs::StdRng, seq::SliceRandom, thread_rng, Rng, SeedableRng};
+use std::{
+	cell::RefCell,
+	collections::HashMap,
+	fmt, io,
+	panic::{self, AssertUnwindSafe},
+	path::PathBuf,
+	rc::Rc,
+};
+
+pub mod audit;
+pub mod belief;
+pub mod bluff;
 pub mod bot;
 pub mod bots;
-
-use crate::bot::{BotInterface, Context, OtherBot};
+pub mod capture;
+pub mod config;
+pub mod profile;
+pub mod replay;
+pub mod result;
+pub mod role;
+pub mod scoring;
+pub mod state;
+pub mod viewer;
+
+use crate::{
+	audit::{AuditEntry, AuditEvent},
+	bluff::BluffReport,
+	bot::{
+		BotInterface, Context, KnownCard, KnownCardSource, OtherBot,
+		PendingChallenge, PendingTurn,
+	},
+	capture::{Capture, CaptureReason},
+	config::{Config, Handicap, TieBreaker},
+	replay::{Decisions, Recorder, Replay, REPLAY_VERSION},
+	result::{Elimination, GameResult, Stalemate},
+	role::RoleSet,
+	scoring::{Points, ScoringRule, ZeroSum},
+	viewer::{Frame, FrameBot},
+};
 
 /// One of the five cards you get in the game of Coup.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -40,6 +73,11 @@ pub enum Card {
 	/// - [Action::Swapping] – Draw two character cards from the deck, choose which (if any) to exchange with your cards, then return two<br>
 	/// - [Counter::Stealing] – Block someone from stealing coins from you
 	Ambassador,
+	/// Replaces the [Card::Ambassador] in the [Deck::Inquisitor].
+	/// - [Action::Swapping] – Draw one character card from the deck, choose whether to exchange it with one of your cards, then return one<br>
+	/// - [Action::Examine] – Look at one card of another player and choose whether to force them to swap it<br>
+	/// - [Counter::Stealing] – Block someone from stealing coins from you
+	Inquisitor,
 	/// - [Action::Assassination] – Pay three coins and try to assassinate another player's character
 	Assassin,
 	/// - [Action::Stealing] – Take two coins from another player
@@ -62,7 +100,8 @@ pub enum Action {
 	Coup(String),
 	/// This standard action can be taken at any time.
 	ForeignAid,
-	/// Take this action with your [Card::Ambassador].
+	/// Take this action with your [Card::Ambassador] or your [Card::Inquisitor]
+	/// when playing with the [Deck::Inquisitor].
 	Swapping,
 	/// This standard action can be taken at any time.
 	Income,
@@ -70,6 +109,16 @@ pub enum Action {
 	Stealing(String),
 	/// Take this action with your [Card::Duke].
 	Tax,
+	/// This Reformation action can be taken at any time.
+	/// Pay one coin into the treasury reserve to change your own [Allegiance] or
+	/// two coins to change the [Allegiance] of another bot.
+	Conversion(String),
+	/// This Reformation action can be taken by claiming you don't have the
+	/// [Card::Duke].
+	/// Take all coins from the treasury reserve.
+	Embezzlement,
+	/// Take this action with your [Card::Inquisitor].
+	Examine(String),
 }
 
 /// Counters are played if something happens that can be countered with a
@@ -80,13 +129,65 @@ pub enum Counter {
 	Assassination,
 	/// Block foreign aid with your [Card::Duke].
 	ForeignAid,
-	/// Block stealing with your [Card::Captain] or your [Card::Ambassador].
+	/// Block stealing with your [Card::Captain] or your [Card::Ambassador] (or
+	/// your [Card::Inquisitor] when playing with the [Deck::Inquisitor]).
 	Stealing,
 }
 
+impl Counter {
+	/// The counter that blocks this action if it can be blocked at all.
+	pub fn from_action(action: &Action) -> Option<Self> {
+		match action {
+			Action::Assassination(_) => Some(Counter::Assassination),
+			Action::ForeignAid => Some(Counter::ForeignAid),
+			Action::Stealing(_) => Some(Counter::Stealing),
+			Action::Coup(_)
+			| Action::Swapping
+			| Action::Income
+			| Action::Tax
+			| Action::Conversion(_)
+			| Action::Embezzlement
+			| Action::Examine(_) => None,
+		}
+	}
+}
+
+/// The two official sets of roles a deck can be made of.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Deck {
+	/// The base game with the [Card::Ambassador].
+	#[default]
+	Classic,
+	/// The Reformation role set with the [Card::Inquisitor] replacing the
+	/// [Card::Ambassador].
+	Inquisitor,
+}
+
+impl Deck {
+	/// The [RoleSet] this deck is made of.
+	pub fn roles(&self) -> RoleSet {
+		match self {
+			Deck::Classic => RoleSet::classic(),
+			Deck::Inquisitor => RoleSet::inquisitor(),
+		}
+	}
+}
+
+/// The two factions of the Reformation expansion.
+/// Bots can't target bots of their own faction while bots of the other faction
+/// are still in the game.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Allegiance {
+	/// The faction loyal to the old order.
+	Loyalist,
+	/// The faction fighting for reform.
+	Reformist,
+}
+
 enum ChallengeRound {
 	Action,
 	Counter,
+	Embezzlement,
 }
 
 /// A collection on all possible moves in the game for bots to analyze.
@@ -106,6 +207,16 @@ pub enum History {
 	ActionStealing { by: String, target: String },
 	/// A bot played a Duke to take 3 coins of tax from the treasury.
 	ActionTax { by: String },
+	/// A bot paid into the treasury reserve to change the allegiance of itself or another bot.
+	ActionConversion { by: String, target: String },
+	/// A bot claimed not to have the Duke to take all coins from the treasury reserve.
+	ActionEmbezzlement { by: String },
+	/// A bot played an Inquisitor to examine a card of another bot.
+	ActionExamine { by: String, target: String },
+	/// A bot forced another bot to swap the card it examined with the Inquisitor.
+	ActionExamineSwap { by: String, target: String },
+	/// A bot drafted its starting cards in draft mode.
+	Draft { by: String },
 
 	/// A bot challenged another bot for having the Assassin.
 	ChallengeAssassin { by: String, target: String },
@@ -115,29 +226,52 @@ pub enum History {
 	ChallengeCaptain { by: String, target: String },
 	/// A bot challenged another bot for having the Duke.
 	ChallengeDuke { by: String, target: String },
+	/// A bot challenged another bot for embezzling while having the Duke.
+	ChallengeEmbezzlement { by: String, target: String },
+	/// A bot challenged another bot for having the Inquisitor.
+	ChallengeInquisitor { by: String, target: String },
+	/// A bot challenged another bot for having the Contessa.
+	ChallengeContessa { by: String, target: String },
 
 	/// Another bot was trying to assassinated so this bot played the Contessa to counter.
 	CounterAssassination { by: String, target: String },
 	/// Another bot was trying to take foreign aid from the treasury so this bot played the Duke to counter.
 	CounterForeignAid { by: String, target: String },
-	/// Another bot was trying to stealing from this bot so it played the Captain or Ambassador to counter.
+	/// Another bot was trying to stealing from this bot so it played the Captain, Ambassador or Inquisitor to counter.
 	CounterStealing { by: String, target: String },
 
 	/// Another bot countered with the Contessa and this bot challenged it for having that card.
 	CounterChallengeContessa { by: String, target: String },
 	/// Another bot countered with the Duke and this bot challenged it for having that card.
 	CounterChallengeDuke { by: String, target: String },
-	/// Another bot countered with the Captain or Ambassador and this bot challenged it for having that card.
+	/// Another bot countered with the Captain, Ambassador or Inquisitor and this bot challenged it for having that card.
 	CounterChallengeCaptainAmbassedor { by: String, target: String },
+	/// Another bot countered with the Assassin, which only a custom
+	/// [RoleSet](role::RoleSet) allows, and this bot challenged it for having that
+	/// card.
+	CounterChallengeAssassin { by: String, target: String },
+
+	/// A bot lost a card and revealed it to the table.
+	CardLoss { by: String, card: Card },
 }
 
 /// The score of the game for all bots.
-pub type Score = Vec<(String, f64)>;
+pub type Score = Vec<(String, Points)>;
+
+// Everything public about a moment in the game used to detect repetitions:
+// turn, discard pile size, treasury reserve and coins, cards and allegiance of
+// each playing bot
+type StateKey = (usize, usize, u8, Vec<(usize, u8, usize, Option<Allegiance>)>);
 
 struct Bot {
 	name: String,
 	coins: u8,
 	cards: Vec<Card>,
+	allegiance: Option<Allegiance>,
+	team: Option<usize>,
+	handicap: Option<Handicap>,
+	known_cards: Vec<KnownCard>,
+	rng: RefCell<StdRng>,
 	interface: Box<dyn BotInterface>,
 }
 
@@ -159,6 +293,13 @@ impl fmt::Debug for Bot {
 	}
 }
 
+impl Bot {
+	// The amount of cards this bot starts each game with
+	fn starting_cards(&self) -> usize {
+		self.handicap.unwrap_or_default().cards
+	}
+}
+
 impl fmt::Display for Bot {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		write!(
@@ -166,7 +307,7 @@ impl fmt::Display for Bot {
 			"\x1b[33m[\x1b[1m{}\x1b[0m \x1b[31m{}{}\x1b[33m 💰{}]\x1b[39m",
 			self.name,
 			"♥".repeat(self.cards.len()),
-			"♡".repeat(2 - self.cards.len()),
+			"♡".repeat(self.starting_cards().saturating_sub(self.cards.len())),
 			self.coins
 		)
 	}
@@ -176,25 +317,68 @@ impl fmt::Display for Bot {
 pub struct Coup {
 	bots: Vec<Bot>,
 	playing_bots: Vec<usize>,
+	seated_bots: Vec<usize>,
+	starting_hands: Vec<(String, Vec<Card>)>,
+	eliminations: Vec<Elimination>,
 	deck: Vec<Card>,
 	discard_pile: Vec<Card>,
 	history: Vec<History>,
 	score: Score,
+	treasury_reserve: u8,
 	turn: usize,
 	moves: usize,
+	seen_states: HashMap<StateKey, usize>,
+	round_snapshots: Vec<Vec<(usize, u8, usize)>>,
+	pending_turn: Option<PendingTurn>,
+	seeds: StdRng,
+	seed: u64,
+	fixed_seed: Option<u64>,
+	rng: StdRng,
+	decisions: Decisions,
+	frames: Option<Vec<Frame>>,
+	audit: Option<Vec<AuditEntry>>,
+	claims: Option<Vec<AuditEntry>>,
+	bluff_report: Option<BluffReport>,
+	capture: Option<Capture>,
+	transcript: Option<RefCell<Vec<String>>>,
+	penalties: usize,
 	log: bool,
 	rounds: u64,
 	round: u64,
+	config: Config,
+	roles: RoleSet,
+	scoring_rule: Box<dyn ScoringRule>,
 }
 
 impl Coup {
 	/// Start a new Coup game by passing in all your bots in a Vec.
 	pub fn new(user_bots: Vec<Box<dyn BotInterface>>) -> Self {
+		Self::new_with_config(user_bots, Config::default())
+	}
+
+	/// Start a new Coup game with a [Config] to change the rules of the game.
+	///
+	/// Panics if [Config::max_players] isn't between 2 and 10, if
+	/// [Config::teams] or [Config::handicaps] don't cover each bot, if a
+	/// [Handicap] doesn't start with 1 or 2 cards, if [Config::draft] isn't
+	/// between 2 and 5 or if a role of [Config::roles] exchanges other than 1
+	/// or 2 cards.
+	pub fn new_with_config(
+		user_bots: Vec<Box<dyn BotInterface>>,
+		config: Config,
+	) -> Self {
+		if let Err(message) = config.check(user_bots.len()) {
+			panic!("{}", message);
+		}
+
+		let mut seeds =
+			config.seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+		let decisions = Rc::new(RefCell::new(None));
 		let mut bots: Vec<Bot> = Vec::new();
 		let mut existing_names: Vec<String> = Vec::new();
-		let mut score: Vec<(String, f64)> = Vec::new();
+		let mut score: Score = Vec::new();
 
-		for bot in user_bots.into_iter() {
+		for (index, bot) in user_bots.into_iter().enumerate() {
 			let base_name = bot.get_name();
 
 			// Generating a unique name for the bot
@@ -211,25 +395,129 @@ impl Coup {
 				name: unique_name.clone(),
 				coins: 2,
 				cards: Vec::new(),
-				interface: bot,
+				allegiance: None,
+				team: config.teams.as_ref().map(|teams| teams[index]),
+				handicap: config
+					.handicaps
+					.as_ref()
+					.map(|handicaps| handicaps[index])
+					.filter(|handicap| *handicap != Handicap::default()),
+				known_cards: vec![],
+				// Each bot gets its own stream so its randomness doesn't depend on
+				// how often other bots were asked something
+				rng: RefCell::new(StdRng::seed_from_u64(seeds.gen())),
+				interface: Box::new(Recorder {
+					name: unique_name.clone(),
+					decisions: decisions.clone(),
+					bot,
+				}),
 			};
 
 			bots.push(bot);
-			score.push((unique_name, 0.0));
+			score.push((unique_name, Points::ZERO));
 		}
 
 		Self {
 			bots,
 			playing_bots: vec![],
+			seated_bots: vec![],
+			starting_hands: vec![],
+			eliminations: vec![],
 			deck: vec![],
 			discard_pile: vec![],
 			history: vec![],
 			score,
+			treasury_reserve: 0,
 			turn: 0,
 			moves: 0,
+			seen_states: HashMap::new(),
+			round_snapshots: vec![],
+			pending_turn: None,
+			seed: 0,
+			fixed_seed: None,
+			rng: StdRng::seed_from_u64(0),
+			seeds,
+			decisions,
+			frames: None,
+			audit: None,
+			claims: None,
+			bluff_report: None,
+			capture: None,
+			transcript: None,
+			penalties: 0,
 			log: true,
 			round: 0,
 			rounds: 0,
+			roles: config.roles.clone().unwrap_or_else(|| config.deck.roles()),
+			scoring_rule: Box::new(ZeroSum),
+			config,
+		}
+	}
+
+	/// Change the [ScoringRule] each game is scored by.
+	/// The default is the zero-sum rule [ZeroSum].
+	pub fn set_scoring_rule(&mut self, scoring_rule: impl ScoringRule + 'static) {
+		self.scoring_rule = Box::new(scoring_rule);
+	}
+
+	/// Write odd games of [Coup::looping] to files, see [Capture].
+	/// Captured games include the audit trail, see [Coup::audit], and their
+	/// [Replay], see [Coup::set_replay].
+	pub fn set_capture(&mut self, capture: Capture) {
+		self.capture = Some(capture);
+		self.transcript = Some(RefCell::new(Vec::new()));
+		self.set_audit(true);
+		self.set_replay(true);
+	}
+
+	/// Record the decisions of the bots in each game for [Coup::replay].
+	pub fn set_replay(&mut self, replay: bool) {
+		*self.decisions.borrow_mut() = if replay { Some(Vec::new()) } else { None };
+	}
+
+	/// Count the bluffs of each bot over all games of [Coup::looping], see
+	/// [BluffReport].
+	pub fn set_bluff_report(&mut self, bluff_report: bool) {
+		self.bluff_report = bluff_report.then(BluffReport::default);
+		self.claims = bluff_report.then(Vec::new);
+	}
+
+	/// The bluffs of each bot over all games of the last [Coup::looping] run.
+	/// `None` unless turned on with [Coup::set_bluff_report].
+	pub fn bluff_report(&self) -> Option<&BluffReport> {
+		self.bluff_report.as_ref()
+	}
+
+	/// Keep the private audit trail of each game, see [Coup::audit].
+	pub fn set_audit(&mut self, audit: bool) {
+		self.audit = if audit { Some(Vec::new()) } else { None };
+	}
+
+	/// The private audit trail of the last game played with the real hand at
+	/// each claim, the cards exchanged with the deck and the order of the deck.
+	/// Empty unless turned on with [Coup::set_audit].
+	pub fn audit(&self) -> &[AuditEntry] {
+		self.audit.as_deref().unwrap_or_default()
+	}
+
+	/// The [Replay] of the last game played.
+	/// Save it to a file to play the game again later with [Replay::run].
+	/// The decisions of the bots are only recorded once turned on with
+	/// [Coup::set_replay] or [Coup::set_capture], without them the replay can't
+	/// be played again.
+	pub fn replay(&self) -> Replay {
+		Replay {
+			version: REPLAY_VERSION,
+			seed: self.seed,
+			config: self.config.clone(),
+			bots: self.bots.iter().map(|bot| bot.name.clone()).collect(),
+			seats: self
+				.seated_bots
+				.iter()
+				.map(|bot_index| self.bots[*bot_index].name.clone())
+				.collect(),
+			decisions: self.decisions.borrow().clone().unwrap_or_default(),
+			history: self.history.clone(),
 		}
 	}
 
@@ -237,30 +525,42 @@ impl Coup {
 	/// This can be used by bots to make sure you get the same amount of cards as
 	/// the engine does.
 	pub fn new_deck() -> Vec<Card> {
-		let mut deck = vec![
-			Card::Ambassador,
-			Card::Ambassador,
-			Card::Ambassador,
-			Card::Assassin,
-			Card::Assassin,
-			Card::Assassin,
-			Card::Captain,
-			Card::Captain,
-			Card::Captain,
-			Card::Contessa,
-			Card::Contessa,
-			Card::Contessa,
-			Card::Duke,
-			Card::Duke,
-			Card::Duke,
-		];
+		Self::new_deck_with(Deck::Classic, 6)
+	}
+
+	/// A public method to get a new deck made of the roles of the given [Deck]
+	/// for a table of the given amount of players.
+	/// Tables of up to 6 players get 3 cards of each role, 7-8 players get 4
+	/// and 9-10 players get 5.
+	pub fn new_deck_with(deck: Deck, players: usize) -> Vec<Card> {
+		let mut deck = deck.roles().deck(players);
 		deck.shuffle(&mut thread_rng());
 		deck
 	}
 
 	fn setup(&mut self) {
-		// A fresh deck
-		self.deck = Coup::new_deck();
+		// Each game gets its own seed so it can be replayed on its own
+		self.seed = self.fixed_seed.take().unwrap_or_else(|| self.seeds.gen());
+		self.rng = StdRng::seed_from_u64(self.seed);
+		for bot in self.bots.iter() {
+			*bot.rng.borrow_mut() = StdRng::seed_from_u64(self.rng.gen());
+		}
+		if let Some(decisions) = self.decisions.borrow_mut().as_mut() {
+			decisions.clear();
+		}
+		if let Some(frames) = &mut self.frames {
+			frames.clear();
+		}
+		if let Some(audit) = &mut self.audit {
+			audit.clear();
+		}
+		if let Some(claims) = &mut self.claims {
+			claims.clear();
+		}
+		if let Some(transcript) = &self.transcript {
+			transcript.borrow_mut().clear();
+		}
+		self.penalties = 0;
 
 		// Put the index of all bots into play so we can shuffle them later
 		self.playing_bots.clear();
@@ -269,26 +569,331 @@ impl Coup {
 		}
 
 		// Shuffle all bots each round and limit them to the max players per game
-		self.playing_bots.shuffle(&mut thread_rng());
-		self.playing_bots.truncate(6);
+		self.playing_bots.shuffle(&mut self.rng);
+		self.playing_bots.truncate(if self.config.two_player {
+			2
+		} else {
+			self.config.max_players
+		});
+		self.seated_bots = self.playing_bots.clone();
 
-		// Give all playing bots cards and coins
-		for bot in self.playing_bots.iter() {
-			let new_cards = vec![self.deck.pop().unwrap(), self.deck.pop().unwrap()];
-			self.bots[*bot].cards = new_cards;
-			self.bots[*bot].coins = 2;
-		}
+		// A fresh deck for the size of this table
+		self.deck = self.roles.deck(self.playing_bots.len());
+		self.shuffle_deck();
 
 		self.discard_pile = vec![];
 		self.history = vec![];
+		self.eliminations = vec![];
+		self.treasury_reserve = 0;
 		self.turn = 0;
 		self.moves = 0;
+		self.seen_states.clear();
+		self.round_snapshots.clear();
+		self.pending_turn = None;
+
+		for bot in self.bots.iter_mut() {
+			bot.known_cards.clear();
+		}
+
+		// Factions alternate around the table in the Reformation expansion
+		for (seat, bot) in self.playing_bots.iter().enumerate() {
+			self.bots[*bot].allegiance = if !self.config.reformation {
+				None
+			} else if seat % 2 == 0 {
+				Some(Allegiance::Loyalist)
+			} else {
+				Some(Allegiance::Reformist)
+			};
+		}
+
+		if self.config.two_player {
+			self.setup_two_player();
+		} else if let Some(draft) = self.config.draft {
+			self.setup_draft(draft);
+		} else {
+			// Give all playing bots cards and coins
+			for bot in self.playing_bots.iter() {
+				let new_cards =
+					vec![self.deck.pop().unwrap(), self.deck.pop().unwrap()];
+				self.bots[*bot].cards = new_cards;
+				self.bots[*bot].coins = 2;
+			}
+		}
+
+		// Handicapped bots return the cards they don't start with to the deck
+		for bot in self.playing_bots.iter() {
+			if let Some(handicap) = self.bots[*bot].handicap {
+				self.bots[*bot].coins = handicap.coins;
+				while self.bots[*bot].cards.len() > handicap.cards {
+					let card = self.bots[*bot].cards.pop().unwrap();
+					self.deck.push(card);
+					self.bots[*bot].known_cards.push(KnownCard {
+						card,
+						source: KnownCardSource::Returned,
+						turn: self.moves,
+					});
+				}
+			}
+		}
+		self.shuffle_deck();
+
+		self.starting_hands = self
+			.playing_bots
+			.iter()
+			.map(|bot| (self.bots[*bot].name.clone(), self.bots[*bot].cards.clone()))
+			.collect();
+	}
+
+	// In draft mode each bot in seat order is shown a few cards from the deck and
+	// picks its two starting cards, the rest goes back into the deck
+	fn setup_draft(&mut self, draft: usize) {
+		for bot in self.playing_bots.iter() {
+			self.bots[*bot].cards = vec![];
+			self.bots[*bot].coins = 2;
+		}
+
+		let mut faulty_bots = Vec::new();
+		for bot in self.playing_bots.clone() {
+			let shown_cards = self.deck.split_off(self.deck.len() - draft);
+			let choice = self.bots[bot].interface.on_draft(
+				shown_cards.clone(),
+				&self.callback_context(self.bots[bot].name.clone()),
+			);
+
+			let mut rest = shown_cards.clone();
+			let mut valid = true;
+			for card in choice.iter() {
+				match rest.iter().position(|c| c == card) {
+					Some(index) => {
+						rest.remove(index);
+					},
+					None => valid = false,
+				}
+			}
+
+			if valid {
+				self.bots[bot].cards = choice.to_vec();
+			} else {
+				faulty_bots.push(self.bots[bot].name.clone());
+				self.bots[bot].cards = shown_cards[..2].to_vec();
+				rest = shown_cards[2..].to_vec();
+			}
+
+			self.push_history(History::Draft {
+				by: self.bots[bot].name.clone(),
+			});
+
+			// The cards that weren't drafted go back into the deck
+			for card in rest.iter() {
+				self.remember_card(bot, *card, KnownCardSource::Returned);
+			}
+			self.deck.extend(rest);
+			self.shuffle_deck();
+		}
+
+		for name in faulty_bots {
+			self.penalize_bot(name, "it drafted cards it wasn't shown");
+		}
+	}
+
+	// In the two-player variant each bot picks its first card from a hand of one
+	// of each role and the starting player only gets one coin
+	fn setup_two_player(&mut self) {
+		let hand = self.roles.cards();
+
+		for bot in self.playing_bots.iter() {
+			self.bots[*bot].cards = vec![];
+			self.bots[*bot].coins = 2;
+		}
+		self.bots[self.playing_bots[0]].coins = 1;
+
+		let mut faulty_bots = Vec::new();
+		let mut chosen_cards = Vec::new();
+		for bot in self.playing_bots.iter() {
+			// Each hand is taken from the deck
+			for card in hand.iter() {
+				if let Some(index) = self.deck.iter().position(|c| c == card) {
+					self.deck.remove(index);
+				}
+			}
+
+			let choice = self.bots[*bot].interface.on_choose_starting_card(
+				hand.clone(),
+				&self.callback_context(self.bots[*bot].name.clone()),
+			);
+
+			if hand.contains(&choice) {
+				chosen_cards.push(choice);
+			} else {
+				faulty_bots.push(self.bots[*bot].name.clone());
+				chosen_cards.push(hand[0]);
+			}
+		}
+
+		// The cards that weren't chosen are set aside for the rest of the game
+		for (bot, choice) in
+			self.playing_bots.clone().into_iter().zip(&chosen_cards)
+		{
+			let mut rest = hand.clone();
+			if let Some(index) = rest.iter().position(|c| c == choice) {
+				rest.remove(index);
+			}
+			for card in rest {
+				self.remember_card(bot, card, KnownCardSource::SetAside);
+			}
+		}
+		self.shuffle_deck();
+
+		for (bot, choice) in self.playing_bots.iter().zip(chosen_cards) {
+			self.bots[*bot].cards = vec![choice, self.deck.pop().unwrap()];
+		}
+
+		for name in faulty_bots {
+			self.penalize_bot(name, "it chose a starting card it wasn't offered");
+		}
 	}
 
-	fn log(message: std::fmt::Arguments, logging: bool) {
-		if logging {
+	fn log(&self, message: std::fmt::Arguments) {
+		if self.log {
 			println!(" {:?}", message);
 		}
+		if let Some(transcript) = &self.transcript {
+			transcript.borrow_mut().push(format!(" {:?}", message));
+		}
+	}
+
+	fn push_history(&mut self, event: History) {
+		self.record_frame();
+		self.record_claim(&event);
+		self.history.push(event);
+	}
+
+	fn shuffle_deck(&mut self) {
+		self.deck.shuffle(&mut self.rng);
+		self.record_audit(AuditEvent::Shuffle {
+			deck: self.deck.clone(),
+		});
+	}
+
+	fn record_audit(&mut self, event: AuditEvent) {
+		let history = self.history.len();
+		if let Some(audit) = &mut self.audit {
+			audit.push(AuditEntry { history, event });
+		}
+	}
+
+	// The real hand of a bot whenever it claims to have or not to have a card,
+	// kept in the audit trail and for the bluff report
+	fn record_claim(&mut self, event: &History) {
+		if self.audit.is_none() && self.claims.is_none() {
+			return;
+		}
+
+		let (by, action, is_counter) = match event {
+			History::ActionAssassination { by, .. } => {
+				(by, Action::Assassination(String::new()), false)
+			},
+			History::ActionSwapping { by } => (by, Action::Swapping, false),
+			History::ActionStealing { by, .. } => {
+				(by, Action::Stealing(String::new()), false)
+			},
+			History::ActionTax { by } => (by, Action::Tax, false),
+			History::ActionEmbezzlement { by } => (by, Action::Embezzlement, false),
+			History::ActionExamine { by, .. } => {
+				(by, Action::Examine(String::new()), false)
+			},
+			History::CounterAssassination { by, .. } => {
+				(by, Action::Assassination(String::new()), true)
+			},
+			History::CounterForeignAid { by, .. } => (by, Action::ForeignAid, true),
+			History::CounterStealing { by, .. } => {
+				(by, Action::Stealing(String::new()), true)
+			},
+			History::ActionCoup { .. }
+			| History::ActionForeignAid { .. }
+			| History::ActionIncome { .. }
+			| History::ActionConversion { .. }
+			| History::ActionExamineSwap { .. }
+			| History::Draft { .. }
+			| History::ChallengeAssassin { .. }
+			| History::ChallengeAmbassador { .. }
+			| History::ChallengeCaptain { .. }
+			| History::ChallengeDuke { .. }
+			| History::ChallengeEmbezzlement { .. }
+			| History::ChallengeInquisitor { .. }
+			| History::ChallengeContessa { .. }
+			| History::CounterChallengeContessa { .. }
+			| History::CounterChallengeDuke { .. }
+			| History::CounterChallengeCaptainAmbassedor { .. }
+			| History::CounterChallengeAssassin { .. }
+			| History::CardLoss { .. } => return,
+		};
+
+		let hand = self.get_bot_by_name(by.clone()).cards.clone();
+		let by = by.clone();
+		let claim = if is_counter {
+			// A counter claims any of the cards that can block the action
+			let cards = self.roles.blockers(&action);
+			AuditEvent::Claim { by, cards, hand }
+		} else if let Some(card) = self.roles.disclaimed_card(&action) {
+			AuditEvent::Disclaim { by, card, hand }
+		} else if let Some(card) = self.roles.claimed_card(&action) {
+			AuditEvent::Claim {
+				by,
+				cards: vec![card],
+				hand,
+			}
+		} else {
+			return;
+		};
+
+		let history = self.history.len();
+		if let Some(claims) = &mut self.claims {
+			claims.push(AuditEntry {
+				history,
+				event: claim.clone(),
+			});
+		}
+		self.record_audit(claim);
+	}
+
+	// The whole table for the viewer, only recorded when replaying for it
+	fn record_frame(&mut self) {
+		if self.frames.is_none() {
+			return;
+		}
+
+		let frame = Frame {
+			bots: self
+				.seated_bots
+				.iter()
+				.map(|bot_index| {
+					let bot = &self.bots[*bot_index];
+					FrameBot {
+						name: bot.name.clone(),
+						coins: bot.coins,
+						cards: bot.cards.clone(),
+						starting_cards: bot.starting_cards(),
+						allegiance: bot.allegiance,
+					}
+				})
+				.collect(),
+			deck: self.deck.clone(),
+			discard_pile: self.discard_pile.clone(),
+			treasury_reserve: self.treasury_reserve,
+		};
+		if let Some(frames) = &mut self.frames {
+			frames.push(frame);
+		}
+	}
+
+	// A card only this bot saw
+	fn remember_card(&mut self, bot: usize, card: Card, source: KnownCardSource) {
+		self.bots[bot].known_cards.push(KnownCard {
+			card,
+			source,
+			turn: self.moves,
+		});
 	}
 
 	fn get_bot_by_name(&self, name: String) -> &Bot {
@@ -305,6 +910,9 @@ impl Coup {
 					name: bot.name.clone(),
 					coins: bot.coins,
 					cards: bot.cards.len() as u8,
+					allegiance: bot.allegiance,
+					team: bot.team,
+					handicap: bot.handicap,
 				}
 			})
 			.filter(|bot| bot.cards != 0)
@@ -320,71 +928,120 @@ impl Coup {
 			discard_pile: self.discard_pile.clone(),
 			history: self.history.clone(),
 			score: self.score.clone(),
+			treasury_reserve: self.treasury_reserve,
+			friendly_fire: self.config.friendly_fire,
+			roles: self.roles.clone(),
+			seats: self
+				.seated_bots
+				.iter()
+				.map(|bot_index| self.bots[*bot_index].name.clone())
+				.collect(),
+			turn: self
+				.playing_bots
+				.get(self.turn)
+				.and_then(|playing| {
+					self.seated_bots.iter().position(|seated| seated == playing)
+				})
+				.unwrap_or_default(),
+			moves: self.moves,
+			max_moves: self.config.max_moves,
+			deck_size: self.deck.len(),
+			acting_bot: self
+				.playing_bots
+				.get(self.turn)
+				.map(|bot_index| self.bots[*bot_index].name.clone())
+				.unwrap_or_default(),
+			pending_turn: self.pending_turn.clone(),
+			known_cards: self.get_bot_by_name(name.clone()).known_cards.clone(),
+			// Only peek at the stream, callbacks draw from it
+			seed: self.get_bot_by_name(name).rng.borrow().clone().gen(),
+			rng: Default::default(),
+		}
+	}
+
+	// The context for a callback of this bot.
+	// Draws the next number of the random stream of the bot so each callback
+	// gets its own seed.
+	fn callback_context(&self, name: String) -> Context {
+		let seed = self.get_bot_by_name(name.clone()).rng.borrow_mut().gen();
+		Context {
+			seed,
+			..self.get_context(name)
 		}
 	}
 
-	fn card_loss(&mut self, name: String) {
+	// A bot loses a card because of the bot "by" or, if None, the engine
+	fn card_loss(&mut self, name: String, by: Option<String>) {
 		if self.get_bot_by_name(name.clone()).cards.is_empty() {
 			// This bot is already dead
 			return;
 		}
-		let context = self.get_context(name.clone());
-		self.bots.iter_mut().for_each(|bot| {
-			let context = Context {
-				coins: bot.coins,
-				cards: bot.cards.clone(),
-				..context.clone()
-			};
-			if bot.name == name {
-				let lost_card = bot.interface.on_card_loss(&context);
-				// Bot discarded a card it didn't have so now we kill it dead
-				if !bot.cards.contains(&lost_card) {
-					Self::log(format_args!("🚨  {} is being penalized because \x1b[33mit discarded a card({:?}) it didn't have\x1b[39m", bot, lost_card), self.log);
-
-					let card = bot.cards.pop().unwrap();
-					let mut lost_cards = format!("{:?}", card);
-					self.discard_pile.push(card);
-
-					if !bot.cards.is_empty() {
-						let card = bot.cards.pop().unwrap();
-						lost_cards =
-							format!("{} and {:?}", lost_cards, card);
-						self.discard_pile.push(card);
-					}
+		let bot_index = self.bots.iter().position(|bot| bot.name == name).unwrap();
+		let lost_card = self.bots[bot_index]
+			.interface
+			.on_card_loss(&self.callback_context(name.clone()));
+
+		// Bot discarded a card it didn't have so now we kill it dead
+		if !self.bots[bot_index].cards.contains(&lost_card) {
+			self.log(format_args!("🚨  {} is being penalized because \x1b[33mit discarded a card({:?}) it didn't have\x1b[39m", self.bots[bot_index], lost_card));
+			self.penalties += 1;
+
+			let mut lost_cards = Vec::new();
+			while let Some(&card) = self.bots[bot_index].cards.last() {
+				self.push_history(History::CardLoss {
+					by: name.clone(),
+					card,
+				});
+				self.bots[bot_index].cards.pop();
+				self.discard_pile.push(card);
+				lost_cards.push(format!("{:?}", card));
+			}
 
-					bot.cards = vec![];
-					Self::log(format_args!("☠️   {} has lost the \x1b[33m{:?}\x1b[39m", bot, lost_cards), self.log);
-				} else {
-					if let Some(index) = bot.cards.iter().position(|&c| c == lost_card) {
-						bot.cards.remove(index);
-					}
-					self.discard_pile.push(lost_card);
-
-					Self::log(format_args!(
-						"{}  {} has lost the \x1b[33m{:?}\x1b[39m",
-						if bot.cards.is_empty() {
-							"☠️ "
-						} else {
-							"💔"
-						},
-						bot,
-						lost_card
-					), self.log);
-				}
+			self.log(format_args!(
+				"☠️   {} has lost the \x1b[33m{:?}\x1b[39m",
+				self.bots[bot_index],
+				lost_cards.join(" and ")
+			));
+		} else {
+			self.push_history(History::CardLoss {
+				by: name.clone(),
+				card: lost_card,
+			});
+			let bot = &mut self.bots[bot_index];
+			if let Some(index) = bot.cards.iter().position(|&c| c == lost_card) {
+				bot.cards.remove(index);
 			}
-		});
+			self.discard_pile.push(lost_card);
+
+			self.log(format_args!(
+				"{}  {} has lost the \x1b[33m{:?}\x1b[39m",
+				if self.bots[bot_index].cards.is_empty() {
+					"☠️ "
+				} else {
+					"💔"
+				},
+				self.bots[bot_index],
+				lost_card
+			));
+		}
+
+		if self.get_bot_by_name(name.clone()).cards.is_empty() {
+			self.eliminations.push(Elimination {
+				name,
+				by,
+				turn: self.moves,
+			});
+		}
 	}
 
 	fn penalize_bot(&mut self, name: String, reason: &str) {
-		Self::log(
-			format_args!(
-				"🚨  {} is being penalized because \x1b[33m{}\x1b[39m",
-				self.get_bot_by_name(name.clone()),
-				reason
-			),
-			self.log,
-		);
-		self.card_loss(name);
+		self.log(format_args!(
+			"🚨  {} is being penalized because \x1b[33m{}\x1b[39m",
+			self.get_bot_by_name(name.clone()),
+			reason
+		));
+		self.penalties += 1;
+		self.card_loss(name, None);
 	}
 
 	fn target_not_found(&self, target: String) -> bool {
@@ -396,56 +1053,114 @@ impl Coup {
 			!= 1
 	}
 
-	fn set_score(&mut self, winners: Vec<String>) {
-		let winner_count = winners.len() as f64;
-		let loser_count = if self.bots.len() > 6 {
-			6.0
+	// Bots can't target their own faction or their teammates
+	fn target_protected(&self, by: String, target: String) -> Option<&str> {
+		if self.target_same_faction(by.clone(), target.clone()) {
+			Some("a bot of its own faction")
+		} else if self.target_teammate(by, target) {
+			Some("a teammate")
 		} else {
-			self.bots.len() as f64
-		} - winner_count;
-		let loser_score = -1.0 / loser_count;
-		let winner_score = -((loser_score * loser_count) / winner_count);
+			None
+		}
+	}
 
-		self.score = self
-			.score
-			.iter()
-			.map(|(name, score)| {
-				if winners.contains(name) {
-					(name.clone(), score + winner_score)
-				} else {
-					(name.clone(), score + loser_score)
-				}
+	// In team play bots can't target their teammates unless friendly fire is on
+	fn target_teammate(&self, by: String, target: String) -> bool {
+		let team = self.get_bot_by_name(by).team;
+		!self.config.friendly_fire
+			&& team.is_some()
+			&& team == self.get_bot_by_name(target).team
+	}
+
+	// In the Reformation expansion bots can't target their own faction as long
+	// as there is a bot of another faction still in the game
+	fn target_same_faction(&self, by: String, target: String) -> bool {
+		let allegiance = self.get_bot_by_name(by).allegiance;
+		allegiance.is_some()
+			&& allegiance == self.get_bot_by_name(target).allegiance
+			&& self.playing_bots.iter().any(|bot| {
+				!self.bots[*bot].cards.is_empty()
+					&& self.bots[*bot].allegiance != allegiance
 			})
-			.collect::<Score>();
 	}
 
-	// We take a card from a bot and replace it with a new one from the deck
-	fn swap_card(&mut self, card: Card, swopee: String) {
-		Self::log(
-			format_args!(
-				"🔄  {} is swapping its card for a new card from the deck",
-				self.get_bot_by_name(swopee.clone())
-			),
-			self.log,
-		);
-		for bot in self.bots.iter_mut() {
-			if bot.name == swopee.clone() {
-				if let Some(index) = bot.cards.iter().position(|&c| c == card) {
-					bot.cards.remove(index);
-				}
-				self.deck.push(card);
-				self.deck.shuffle(&mut thread_rng());
+	// The scoring rule decides the points for this game which are added to the
+	// score of each bot
+	fn set_score(&mut self, result: &GameResult) {
+		let points = self.scoring_rule.score(result);
 
-				let mut new_cards = bot.cards.clone();
-				new_cards.push(self.deck.pop().unwrap());
-				bot.cards = new_cards;
+		for (name, score) in self.score.iter_mut() {
+			if let Some((_, point)) = points.iter().find(|(bot, _)| bot == name) {
+				*score += *point;
 			}
 		}
 	}
 
+	// Everything that happened in this game for scoring and to return from play
+	fn game_result(
+		&self,
+		winners: Vec<String>,
+		stalemate: Option<Stalemate>,
+		tie_breaker: Option<TieBreaker>,
+	) -> GameResult {
+		GameResult {
+			winners,
+			moves: self.moves,
+			stalemate,
+			tie_breaker,
+			starting_hands: self.starting_hands.clone(),
+			handicaps: self
+				.seated_bots
+				.iter()
+				.filter_map(|bot_index| {
+					let bot = &self.bots[*bot_index];
+					bot.handicap.map(|handicap| (bot.name.clone(), handicap))
+				})
+				.collect(),
+			teams: self
+				.seated_bots
+				.iter()
+				.filter_map(|bot_index| {
+					let bot = &self.bots[*bot_index];
+					bot.team.map(|team| (bot.name.clone(), team))
+				})
+				.collect(),
+			eliminations: self.eliminations.clone(),
+		}
+	}
+
+	// We take a card from a bot and replace it with a new one from the deck
+	fn swap_card(&mut self, card: Card, swopee: String) {
+		self.log(format_args!(
+			"🔄  {} is swapping its card for a new card from the deck",
+			self.get_bot_by_name(swopee.clone())
+		));
+		let bot_index =
+			self.bots.iter().position(|bot| bot.name == swopee).unwrap();
+		let bot = &mut self.bots[bot_index];
+		if let Some(index) = bot.cards.iter().position(|&c| c == card) {
+			bot.cards.remove(index);
+		}
+		bot.known_cards.push(KnownCard {
+			card,
+			source: KnownCardSource::Shuffled,
+			turn: self.moves,
+		});
+		self.deck.push(card);
+		self.shuffle_deck();
+
+		let new_card = self.deck.pop().unwrap();
+		self.bots[bot_index].cards.push(new_card);
+		self.record_audit(AuditEvent::Exchange {
+			by: swopee,
+			drawn: vec![new_card],
+			returned: vec![card],
+		});
+	}
+
 	/// Playing a game which means we setup the table, give each bots their cards
 	/// and coins and start the game loop.
-	pub fn play(&mut self) {
+	pub fn play(&mut self) -> GameResult {
 		self.setup();
 
 		// Logo
@@ -455,61 +1170,184 @@ impl Coup {
 			spaceless: true,
 			..Options::default()
 		});
-		Self::log(
-			format_args!(
-				"\n\n{}\x1b[4Dv{}\n\n",
-				output.text,
-				env!("CARGO_PKG_VERSION")
-			),
-			self.log,
-		);
+		self.log(format_args!(
+			"\n\n{}\x1b[4Dv{}\n\n",
+			output.text,
+			env!("CARGO_PKG_VERSION")
+		));
 
 		let bots = self
 			.playing_bots
 			.iter()
 			.map(|bot_index| format!("{}", self.bots[*bot_index]))
 			.collect::<Vec<String>>();
-		Self::log(
-			format_args!("🤺  This rounds player:\n     {}\n", bots.join("\n     "),),
-			self.log,
-		);
+		self.log(format_args!(
+			"🤺  This rounds player:\n     {}\n",
+			bots.join("\n     "),
+		));
 
 		// Let's play
-		while self.playing_bots.len() > 1 {
+		let mut stalemate = None;
+		while !self.game_over() {
 			self.game_loop();
 
-			if self.moves >= 1000 {
+			stalemate = self.stalemate();
+			if let Some(reason) = stalemate {
+				self.log(format_args!(
+					"💤  The game was stopped in a stalemate: {:?}",
+					reason
+				));
 				break;
 			}
 		}
 
+		let survivors = self.playing_bots.clone();
+		let (survivors, tie_breaker) = match stalemate {
+			Some(_) => {
+				let (survivors, tie_breaker) = self.break_tie(survivors);
+				self.log(format_args!("⚖️  The tie was broken by: {:?}", tie_breaker));
+				(survivors, Some(tie_breaker))
+			},
+			None => (survivors, None),
+		};
+
+		// In team play the whole team wins even if some members are dead
+		let winning_teams = survivors
+			.iter()
+			.map(|bot_index| self.bots[*bot_index].team)
+			.collect::<Vec<Option<usize>>>();
 		let winners = self
-			.playing_bots
+			.seated_bots
 			.iter()
+			.filter(|bot_index| {
+				survivors.contains(bot_index)
+					|| (self.bots[**bot_index].team.is_some()
+						&& winning_teams.contains(&self.bots[**bot_index].team))
+			})
 			.map(|bot_index| self.bots[*bot_index].name.clone())
 			.collect::<Vec<String>>();
 
-		self.set_score(winners.clone());
+		self.record_frame();
+		let result = self.game_result(winners.clone(), stalemate, tie_breaker);
+		self.set_score(&result);
 
-		Self::log(
-			format_args!(
-				"\n 🎉🎉🎉 The winner{} \x1b[1m{}\x1b[0m \x1b[90min {} moves\x1b[39m\n",
-				if winners.len() > 1 { "s are" } else { " is" },
-				winners.join(" and "),
-				self.moves
-			),
-			self.log,
+		self.log(format_args!(
+			"\n 🎉🎉🎉 The winner{} \x1b[1m{}\x1b[0m \x1b[90min {} moves\x1b[39m\n",
+			if winners.len() > 1 { "s are" } else { " is" },
+			winners.join(" and "),
+			self.moves
+		));
+
+		result
+	}
+
+	// A game is stopped when it can't progress anymore
+	fn stalemate(&mut self) -> Option<Stalemate> {
+		if self.moves >= self.config.max_moves {
+			return Some(Stalemate::MaxMoves);
+		}
+
+		let state: StateKey = (
+			self.turn,
+			self.discard_pile.len(),
+			self.treasury_reserve,
+			self
+				.playing_bots
+				.iter()
+				.map(|bot_index| {
+					let bot = &self.bots[*bot_index];
+					(*bot_index, bot.coins, bot.cards.len(), bot.allegiance)
+				})
+				.collect(),
 		);
+		let repetitions = self.seen_states.entry(state).or_insert(0);
+		*repetitions += 1;
+		if let Some(max_repetitions) = self.config.stalemate_repetitions {
+			if *repetitions >= max_repetitions {
+				return Some(Stalemate::Repetition);
+			}
+		}
+
+		// A round is over once the turn comes back to the first bot
+		if self.turn == 0 {
+			self.round_snapshots.push(
+				self
+					.playing_bots
+					.iter()
+					.map(|bot_index| {
+						let bot = &self.bots[*bot_index];
+						(*bot_index, bot.coins, bot.cards.len())
+					})
+					.collect(),
+			);
+			if let Some(rounds) = self.config.stalemate_rounds {
+				let snapshots = self.round_snapshots.len();
+				if snapshots > rounds
+					&& self.round_snapshots[snapshots - 1 - rounds]
+						== self.round_snapshots[snapshots - 1]
+				{
+					return Some(Stalemate::NoProgress);
+				}
+			}
+		}
+
+		None
+	}
+
+	// Narrow down the bots still in a stalemated game with the configured
+	// tie-breakers until only one bot or team is left
+	fn break_tie(&self, mut survivors: Vec<usize>) -> (Vec<usize>, TieBreaker) {
+		for tie_breaker in self.config.tie_breakers.iter() {
+			let measure = |bot_index: &usize| match tie_breaker {
+				TieBreaker::MostInfluence => self.bots[*bot_index].cards.len(),
+				TieBreaker::MostCoins => self.bots[*bot_index].coins as usize,
+				TieBreaker::Shared => 0,
+			};
+			let best = survivors.iter().map(measure).max().unwrap_or(0);
+			survivors.retain(|bot_index| measure(bot_index) == best);
+
+			let first_team =
+				survivors.first().and_then(|bot_index| self.bots[*bot_index].team);
+			if *tie_breaker == TieBreaker::Shared
+				|| survivors.len() == 1
+				|| (first_team.is_some()
+					&& survivors
+						.iter()
+						.all(|bot_index| self.bots[*bot_index].team == first_team))
+			{
+				return (survivors, *tie_breaker);
+			}
+		}
+
+		(survivors, TieBreaker::Shared)
+	}
+
+	// The game is over once there is only one bot or one team left
+	fn game_over(&self) -> bool {
+		let team = self
+			.playing_bots
+			.first()
+			.and_then(|bot_index| self.bots[*bot_index].team);
+		self.playing_bots.len() <= 1
+			|| (team.is_some()
+				&& self
+					.playing_bots
+					.iter()
+					.all(|bot_index| self.bots[*bot_index].team == team))
 	}
 
 	fn game_loop(&mut self) {
 		self.moves += 1;
+		self.pending_turn = None;
 
-		let context =
-			self.get_context(self.bots[self.playing_bots[self.turn]].name.clone());
+		let context = self
+			.callback_context(self.bots[self.playing_bots[self.turn]].name.clone());
 
-		// If you have 10 or more coins you must coup
-		let action = if self.bots[self.playing_bots[self.turn]].coins >= 10 {
+		// If you have 10 or more coins you must coup, unless there is nobody you
+		// may coup
+		let action = if self.bots[self.playing_bots[self.turn]].coins >= 10
+			&& !context.targets().is_empty()
+		{
 			let target = self.bots[self.playing_bots[self.turn]]
 				.interface
 				.on_auto_coup(&context);
@@ -519,25 +1357,47 @@ impl Coup {
 		};
 
 		match action {
-			Action::Assassination(target_name) => {
-				if self.target_not_found(target_name.clone()) {
+			// Character actions need a role in the deck that allows them
+			Action::Assassination(_)
+			| Action::Swapping
+			| Action::Stealing(_)
+			| Action::Tax
+			| Action::Embezzlement
+			| Action::Examine(_)
+				if !self.roles.allows(&action) =>
+			{
+				self.penalize_bot(
+					context.name.clone(),
+					"it tried to take an action no role in the deck allows",
+				);
+			},
+			Action::Assassination(target_name) => {
+				if self.target_not_found(target_name.clone()) {
 					self.penalize_bot(
 						context.name.clone(),
 						"it tried to assassinate an unknown bot",
 					);
+				} else if let Some(protection) =
+					self.target_protected(context.name.clone(), target_name.clone())
+				{
+					self.penalize_bot(
+						context.name.clone(),
+						&format!("it tried to assassinate {}", protection),
+					);
 				} else {
-					self.history.push(History::ActionAssassination {
+					self.push_history(History::ActionAssassination {
 						by: context.name.clone(),
 						target: target_name.clone(),
 					});
-					Self::log(
-						format_args!(
-							"🃏  {} assassinates {} with the \x1b[33mAssassin\x1b[39m",
-							self.bots[self.playing_bots[self.turn]],
-							self.get_bot_by_name(target_name.clone())
-						),
-						self.log,
-					);
+					self.log(format_args!(
+						"🃏  {} assassinates {} with the \x1b[33m{:?}\x1b[39m",
+						self.bots[self.playing_bots[self.turn]],
+						self.get_bot_by_name(target_name.clone()),
+						self
+							.roles
+							.claimed_card(&Action::Assassination(String::new()))
+							.unwrap(),
+					));
 					self.challenge_and_counter_round(
 						Action::Assassination(target_name.clone()),
 						target_name,
@@ -550,59 +1410,55 @@ impl Coup {
 						context.name.clone(),
 						"it tried to coup an unknown bot",
 					);
+				} else if let Some(protection) =
+					self.target_protected(context.name.clone(), target_name.clone())
+				{
+					self.penalize_bot(
+						context.name.clone(),
+						&format!("it tried to coup {}", protection),
+					);
 				} else {
-					self.history.push(History::ActionCoup {
+					self.push_history(History::ActionCoup {
 						by: context.name.clone(),
 						target: target_name.clone(),
 					});
-					Self::log(
-						format_args!(
-							"🃏  {} \x1b[33mcoups\x1b[39m {}",
-							self.bots[self.playing_bots[self.turn]],
-							self.get_bot_by_name(target_name.clone())
-						),
-						self.log,
-					);
+					self.log(format_args!(
+						"🃏  {} \x1b[33mcoups\x1b[39m {}",
+						self.bots[self.playing_bots[self.turn]],
+						self.get_bot_by_name(target_name.clone())
+					));
 					self.action_couping(target_name.clone());
 				}
 			},
 			Action::ForeignAid => {
-				self.history.push(History::ActionForeignAid {
+				self.push_history(History::ActionForeignAid {
 					by: context.name.clone(),
 				});
-				Self::log(
-					format_args!(
-						"🃏  {} takes \x1b[33mforeign aid\x1b[39m",
-						self.bots[self.playing_bots[self.turn]],
-					),
-					self.log,
-				);
+				self.log(format_args!(
+					"🃏  {} takes \x1b[33mforeign aid\x1b[39m",
+					self.bots[self.playing_bots[self.turn]],
+				));
 				self.counter_round_only();
 			},
 			Action::Swapping => {
-				self.history.push(History::ActionSwapping {
+				self.push_history(History::ActionSwapping {
 					by: context.name.clone(),
 				});
-				Self::log(
-					format_args!(
-						"🃏  {} swaps cards with \x1b[33mthe Ambassador\x1b[39m",
-						self.bots[self.playing_bots[self.turn]]
-					),
-					self.log,
-				);
+				self.log(format_args!(
+					"🃏  {} swaps cards with the \x1b[33m{:?}\x1b[39m",
+					self.bots[self.playing_bots[self.turn]],
+					self.roles.claimed_card(&Action::Swapping).unwrap(),
+				));
 				self.challenge_round_only(Action::Swapping);
 			},
 			Action::Income => {
-				self.history.push(History::ActionIncome {
+				self.push_history(History::ActionIncome {
 					by: context.name.clone(),
 				});
-				Self::log(
-					format_args!(
-						"🃏  {} takes \x1b[33ma coin\x1b[39m",
-						self.bots[self.playing_bots[self.turn]]
-					),
-					self.log,
-				);
+				self.log(format_args!(
+					"🃏  {} takes \x1b[33ma coin\x1b[39m",
+					self.bots[self.playing_bots[self.turn]]
+				));
 				self.action_income();
 			},
 			Action::Stealing(target_name) => {
@@ -611,19 +1467,23 @@ impl Coup {
 						context.name.clone(),
 						"it tried to steal from an unknown bot",
 					);
+				} else if let Some(protection) =
+					self.target_protected(context.name.clone(), target_name.clone())
+				{
+					self.penalize_bot(
+						context.name.clone(),
+						&format!("it tried to steal from {}", protection),
+					);
 				} else {
-					self.history.push(History::ActionStealing {
+					self.push_history(History::ActionStealing {
 						by: context.name.clone(),
 						target: target_name.clone(),
 					});
-					Self::log(
-						format_args!(
-							"🃏  {} \x1b[33msteals 2 coins\x1b[39m from {}",
-							self.bots[self.playing_bots[self.turn]],
-							self.get_bot_by_name(target_name.clone()),
-						),
-						self.log,
-					);
+					self.log(format_args!(
+						"🃏  {} \x1b[33msteals 2 coins\x1b[39m from {}",
+						self.bots[self.playing_bots[self.turn]],
+						self.get_bot_by_name(target_name.clone()),
+					));
 					self.challenge_and_counter_round(
 						Action::Stealing(target_name.clone()),
 						target_name,
@@ -631,18 +1491,84 @@ impl Coup {
 				}
 			},
 			Action::Tax => {
-				self.history.push(History::ActionTax {
+				self.push_history(History::ActionTax {
 					by: context.name.clone(),
 				});
-				Self::log(
-					format_args!(
-						"🃏  {} takes tax with the \x1b[33mDuke\x1b[39m",
-						self.bots[self.playing_bots[self.turn]],
-					),
-					self.log,
-				);
+				self.log(format_args!(
+					"🃏  {} takes tax with the \x1b[33m{:?}\x1b[39m",
+					self.bots[self.playing_bots[self.turn]],
+					self.roles.claimed_card(&Action::Tax).unwrap(),
+				));
 				self.challenge_round_only(Action::Tax);
 			},
+			Action::Conversion(target_name) => {
+				if !self.config.reformation {
+					self.penalize_bot(
+						context.name.clone(),
+						"it tried to convert without the Reformation expansion",
+					);
+				} else if self.target_not_found(target_name.clone()) {
+					self.penalize_bot(
+						context.name.clone(),
+						"it tried to convert an unknown bot",
+					);
+				} else {
+					self.push_history(History::ActionConversion {
+						by: context.name.clone(),
+						target: target_name.clone(),
+					});
+					self.log(format_args!(
+						"🃏  {} \x1b[33mconverts\x1b[39m {}",
+						self.bots[self.playing_bots[self.turn]],
+						self.get_bot_by_name(target_name.clone()),
+					));
+					self.action_conversion(target_name);
+				}
+			},
+			Action::Embezzlement => {
+				if !self.config.reformation {
+					self.penalize_bot(
+						context.name.clone(),
+						"it tried to embezzle without the Reformation expansion",
+					);
+				} else {
+					self.push_history(History::ActionEmbezzlement {
+						by: context.name.clone(),
+					});
+					self.log(format_args!(
+						"🃏  {} \x1b[33membezzles\x1b[39m the treasury reserve",
+						self.bots[self.playing_bots[self.turn]],
+					));
+					self.challenge_round_only(Action::Embezzlement);
+				}
+			},
+			Action::Examine(target_name) => {
+				if self.target_not_found(target_name.clone()) {
+					self.penalize_bot(
+						context.name.clone(),
+						"it tried to examine an unknown bot",
+					);
+				} else if let Some(protection) =
+					self.target_protected(context.name.clone(), target_name.clone())
+				{
+					self.penalize_bot(
+						context.name.clone(),
+						&format!("it tried to examine {}", protection),
+					);
+				} else {
+					self.push_history(History::ActionExamine {
+						by: context.name.clone(),
+						target: target_name.clone(),
+					});
+					self.log(format_args!(
+						"🃏  {} examines {} with the \x1b[33m{:?}\x1b[39m",
+						self.bots[self.playing_bots[self.turn]],
+						self.get_bot_by_name(target_name.clone()),
+						self.roles.claimed_card(&Action::Examine(String::new())).unwrap(),
+					));
+					self.challenge_round_only(Action::Examine(target_name));
+				}
+			},
 		}
 
 		// Let's filter out all dead bots
@@ -676,6 +1602,42 @@ impl Coup {
 			.collect()
 	}
 
+	// The action of this turn is now open to challenges and counters
+	fn start_pending_turn(&mut self, action: &Action, target: Option<String>) {
+		self.pending_turn = Some(PendingTurn {
+			actor: self.bots[self.playing_bots[self.turn]].name.clone(),
+			action: action.clone(),
+			target,
+			claimed_card: self
+				.roles
+				.claimed_card(action)
+				.or_else(|| self.roles.disclaimed_card(action)),
+			counterer: None,
+			challenges: vec![],
+		});
+	}
+
+	fn record_pending_challenge(
+		&mut self,
+		by: String,
+		target: String,
+		successful: bool,
+	) {
+		if let Some(pending_turn) = &mut self.pending_turn {
+			pending_turn.challenges.push(PendingChallenge {
+				by,
+				target,
+				successful,
+			});
+		}
+	}
+
+	fn record_pending_counter(&mut self, counterer: String) {
+		if let Some(pending_turn) = &mut self.pending_turn {
+			pending_turn.counterer = Some(counterer);
+		}
+	}
+
 	fn challenge_and_counter_round(
 		&mut self,
 		action: Action,
@@ -683,6 +1645,7 @@ impl Coup {
 	) {
 		// THE CHALLENGE ROUND
 		let playing_bot_name = self.bots[self.playing_bots[self.turn]].name.clone();
+		self.start_pending_turn(&action, Some(target_name.clone()));
 		// On Action::Assassination and Action::Stealing
 		// Does anyone want to challenge this action?
 		if let Some(challenger) = self.challenge_round(
@@ -696,20 +1659,15 @@ impl Coup {
 				playing_bot_name.clone(),
 				challenger.clone(),
 			);
+			self.record_pending_challenge(
+				challenger.clone(),
+				playing_bot_name.clone(),
+				success,
+			);
 			if !success {
 				// The challenge was unsuccessful
 				// Discard the card and pick up a new card from the deck
-				let discard_card = match action {
-					Action::Assassination(_) => Card::Assassin,
-					Action::Stealing(_) => Card::Captain,
-					Action::Coup(_)
-					| Action::ForeignAid
-					| Action::Swapping
-					| Action::Income
-					| Action::Tax => {
-						unreachable!("Challenge and counter not called on other actions")
-					},
-				};
+				let discard_card = self.roles.claimed_card(&action).unwrap();
 				self.swap_card(discard_card, playing_bot_name.clone());
 			} else {
 				// The challenge was successful so we stop a counter round
@@ -726,24 +1684,26 @@ impl Coup {
 		if !self.get_bot_by_name(target_name.clone()).cards.is_empty() {
 			// THE COUNTER CHALLENGE ROUND
 			// Does the target want to counter this action?
-			let counter =
-				self.get_bot_by_name(target_name.clone()).interface.on_counter(
+			// Only if there is a role in the deck that can block it
+			let counter = !self.roles.blockers(&action).is_empty()
+				&& self.get_bot_by_name(target_name.clone()).interface.on_counter(
 					&action,
 					playing_bot_name.clone(),
-					&self.get_context(target_name.clone()),
+					&self.callback_context(target_name.clone()),
 				);
 
 			if counter {
 				// The bot target_name is countering the action so we now ask the
 				// table if anyone would like to challenge this counter
+				self.record_pending_counter(target_name.clone());
 				match action {
 					Action::Assassination(_) => {
-						self.history.push(History::CounterAssassination {
+						self.push_history(History::CounterAssassination {
 							by: target_name.clone(),
 							target: playing_bot_name.clone(),
 						})
 					},
-					Action::Stealing(_) => self.history.push(History::CounterStealing {
+					Action::Stealing(_) => self.push_history(History::CounterStealing {
 						by: target_name.clone(),
 						target: playing_bot_name.clone(),
 					}),
@@ -751,41 +1711,35 @@ impl Coup {
 					| Action::ForeignAid
 					| Action::Swapping
 					| Action::Income
-					| Action::Tax => {
+					| Action::Tax
+					| Action::Conversion(_)
+					| Action::Embezzlement
+					| Action::Examine(_) => {
 						unreachable!("Challenge and counter not called on other actions")
 					},
 				};
-				Self::log(
-					format_args!(
-						"🛑  {} was countered by {}",
-						self.get_bot_by_name(playing_bot_name.clone()),
-						self.get_bot_by_name(target_name.clone()),
-					),
-					self.log,
-				);
+				self.log(format_args!(
+					"🛑  {} was countered by {}",
+					self.get_bot_by_name(playing_bot_name.clone()),
+					self.get_bot_by_name(target_name.clone()),
+				));
 
 				if let Some(counter_challenge) = self.challenge_round(
 					ChallengeRound::Counter,
 					&action,
 					target_name.clone(),
 				) {
-					let counter_card = match action {
-						Action::Assassination(_) => Counter::Assassination,
-						Action::Stealing(_) => Counter::Stealing,
-						Action::Coup(_)
-						| Action::ForeignAid
-						| Action::Swapping
-						| Action::Income
-						| Action::Tax => {
-							unreachable!("Challenge and counter not called on other actions")
-						},
-					};
 					// The bot counter_challenge.by is challenging this action
 					let success = self.resolve_counter_challenge(
-						counter_card,
+						&action,
 						target_name.clone(),
 						counter_challenge.clone(),
 					);
+					self.record_pending_challenge(
+						counter_challenge.clone(),
+						target_name.clone(),
+						success,
+					);
 					if success {
 						// The challenge was successful so the player who countered gets a
 						// penalty but the action is still performed
@@ -798,7 +1752,10 @@ impl Coup {
 							| Action::ForeignAid
 							| Action::Swapping
 							| Action::Income
-							| Action::Tax => unreachable!(
+							| Action::Tax
+							| Action::Conversion(_)
+							| Action::Embezzlement
+							| Action::Examine(_) => unreachable!(
 								"Challenge and counter not called on other actions"
 							),
 						}
@@ -818,7 +1775,10 @@ impl Coup {
 					| Action::ForeignAid
 					| Action::Swapping
 					| Action::Income
-					| Action::Tax => {
+					| Action::Tax
+					| Action::Conversion(_)
+					| Action::Embezzlement
+					| Action::Examine(_) => {
 						unreachable!("Challenge and counter not called on other actions")
 					},
 				}
@@ -829,44 +1789,58 @@ impl Coup {
 	fn challenge_round_only(&mut self, action: Action) {
 		// THE CHALLENGE ROUND
 		let playing_bot_name = self.bots[self.playing_bots[self.turn]].name.clone();
-		// On Action::Swapping and Action::Tax
+		// On Action::Swapping, Action::Tax, Action::Embezzlement and Action::Examine
 		// Does anyone want to challenge this action?
-		if let Some(challenger) = self.challenge_round(
-			ChallengeRound::Action,
-			&action,
-			playing_bot_name.clone(),
-		) {
+		let target = match &action {
+			Action::Examine(target) => Some(target.clone()),
+			_ => None,
+		};
+		self.start_pending_turn(&action, target);
+		let disclaimed = self.roles.disclaimed_card(&action).is_some();
+		let challenge_type = if disclaimed {
+			ChallengeRound::Embezzlement
+		} else {
+			ChallengeRound::Action
+		};
+		if let Some(challenger) =
+			self.challenge_round(challenge_type, &action, playing_bot_name.clone())
+		{
 			// The bot "challenger" is challenging this action
 			let success = self.resolve_challenge(
 				action.clone(),
 				playing_bot_name.clone(),
 				challenger.clone(),
 			);
+			self.record_pending_challenge(
+				challenger.clone(),
+				playing_bot_name.clone(),
+				success,
+			);
 			if !success {
 				// The challenge was unsuccessful
 				// Discard the card and pick up a new card from the deck
-				let discard_card = match action {
-					Action::Swapping => Card::Ambassador,
-					Action::Tax => Card::Duke,
-					Action::Coup(_)
-					| Action::Assassination(_)
-					| Action::ForeignAid
-					| Action::Income
-					| Action::Stealing(_) => {
-						unreachable!("Challenge only not called on other actions")
-					},
+				let discard_cards = if disclaimed {
+					// The bot had to reveal its whole hand to prove it has no such card
+					self.get_bot_by_name(playing_bot_name.clone()).cards.clone()
+				} else {
+					vec![self.roles.claimed_card(&action).unwrap()]
 				};
-				self.swap_card(discard_card, playing_bot_name.clone());
+				for discard_card in discard_cards {
+					self.swap_card(discard_card, playing_bot_name.clone());
+				}
 
 				// The challenge was unsuccessful so let's do the thing
 				match action {
 					Action::Swapping => self.action_swapping(),
 					Action::Tax => self.action_tax(),
+					Action::Embezzlement => self.action_embezzlement(),
+					Action::Examine(target) => self.action_examine(target),
 					Action::Coup(_)
 					| Action::Assassination(_)
 					| Action::ForeignAid
 					| Action::Income
-					| Action::Stealing(_) => {
+					| Action::Stealing(_)
+					| Action::Conversion(_) => {
 						unreachable!("Challenge only not called on other actions")
 					},
 				}
@@ -876,11 +1850,14 @@ impl Coup {
 			match action {
 				Action::Swapping => self.action_swapping(),
 				Action::Tax => self.action_tax(),
+				Action::Embezzlement => self.action_embezzlement(),
+				Action::Examine(target) => self.action_examine(target),
 				Action::Coup(_)
 				| Action::Assassination(_)
 				| Action::ForeignAid
 				| Action::Income
-				| Action::Stealing(_) => {
+				| Action::Stealing(_)
+				| Action::Conversion(_) => {
 					unreachable!("Challenge only not called on other actions")
 				},
 			}
@@ -892,16 +1869,29 @@ impl Coup {
 		// THE COUNTER CHALLENGE ROUND
 		// On Action::ForeignAid
 		// Does anyone want to counter this action?
+		self.start_pending_turn(&Action::ForeignAid, None);
 		let mut counterer_name = String::new();
-		for bot_index in
-			self.get_bot_list_starting_from_name(&playing_bot_name).iter()
-		{
+		// Nobody can counter if no role in the deck blocks this action
+		let bot_list = if self.roles.blockers(&Action::ForeignAid).is_empty() {
+			vec![]
+		} else {
+			self.get_bot_list_starting_from_name(&playing_bot_name)
+		};
+		for bot_index in bot_list.iter() {
 			let bot = &self.bots[*bot_index];
 
+			// Bots can't block foreign aid of their own faction or their teammates
+			if self
+				.target_protected(bot.name.clone(), playing_bot_name.clone())
+				.is_some()
+			{
+				continue;
+			}
+
 			let countering = bot.interface.on_counter(
 				&Action::ForeignAid,
 				playing_bot_name.clone(),
-				&self.get_context(playing_bot_name.clone()),
+				&self.callback_context(bot.name.clone()),
 			);
 
 			if countering {
@@ -911,18 +1901,16 @@ impl Coup {
 		}
 
 		if !counterer_name.is_empty() {
-			self.history.push(History::CounterForeignAid {
+			self.record_pending_counter(counterer_name.clone());
+			self.push_history(History::CounterForeignAid {
 				by: counterer_name.clone(),
 				target: playing_bot_name.clone(),
 			});
-			Self::log(
-				format_args!(
-					"🛑  {} was countered by {}",
-					self.get_bot_by_name(playing_bot_name.clone()),
-					self.get_bot_by_name(counterer_name.clone()),
-				),
-				self.log,
-			);
+			self.log(format_args!(
+				"🛑  {} was countered by {}",
+				self.get_bot_by_name(playing_bot_name.clone()),
+				self.get_bot_by_name(counterer_name.clone()),
+			));
 
 			// The bot counterer_name is countering the action so we now ask the table
 			// if anyone would like to challenge this counter
@@ -933,10 +1921,15 @@ impl Coup {
 			) {
 				// The bot counter_challenge.by is challenging this action
 				let success = self.resolve_counter_challenge(
-					Counter::ForeignAid,
+					&Action::ForeignAid,
 					counterer_name.clone(),
 					counter_challenge.clone(),
 				);
+				self.record_pending_challenge(
+					counter_challenge.clone(),
+					counterer_name.clone(),
+					success,
+				);
 				if success {
 					self.action_foraign_aid();
 				}
@@ -957,7 +1950,7 @@ impl Coup {
 		for bot_index in self.get_bot_list_starting_from_name(&by).iter() {
 			let bot = &self.bots[*bot_index];
 
-			let context = self.get_context(bot.name.clone());
+			let context = self.callback_context(bot.name.clone());
 
 			let challenging = match challenge_type {
 				ChallengeRound::Action => {
@@ -966,17 +1959,17 @@ impl Coup {
 				ChallengeRound::Counter => {
 					bot.interface.on_challenge_counter_round(action, by.clone(), &context)
 				},
+				ChallengeRound::Embezzlement => {
+					bot.interface.on_challenge_embezzlement(by.clone(), &context)
+				},
 			};
 
 			if challenging {
-				Self::log(
-					format_args!(
-						"❓  {} was challenged by {}",
-						self.get_bot_by_name(by),
-						bot
-					),
-					self.log,
-				);
+				self.log(format_args!(
+					"❓  {} was challenged by {}",
+					self.get_bot_by_name(by),
+					bot
+				));
 				return Some(bot.name.clone());
 			}
 		}
@@ -990,60 +1983,55 @@ impl Coup {
 		player: String,
 		challenger: String,
 	) -> bool {
-		self.history.push(match action {
-			Action::Assassination(_) => History::ChallengeAssassin {
-				by: challenger.clone(),
-				target: player.clone(),
-			},
-			Action::Swapping => History::ChallengeAmbassador {
-				by: challenger.clone(),
-				target: player.clone(),
-			},
-			Action::Stealing(_) => History::ChallengeCaptain {
-				by: challenger.clone(),
-				target: player.clone(),
-			},
-			Action::Tax => History::ChallengeDuke {
-				by: challenger.clone(),
-				target: player.clone(),
-			},
-			Action::Coup(_) | Action::Income | Action::ForeignAid => {
-				unreachable!("Can't challenge Coup, Income or ForeignAid")
-			},
+		// A bot either claims to have a card or, when embezzling, claims not to
+		let (card, disclaimed) = match self.roles.claimed_card(&action) {
+			Some(card) => (card, false),
+			None => (
+				self.roles.disclaimed_card(&action).expect(
+					"Can't challenge actions that no role in the deck claims or disclaims",
+				),
+				true,
+			),
+		};
+
+		let by = challenger.clone();
+		let target = player.clone();
+		self.push_history(if disclaimed {
+			History::ChallengeEmbezzlement { by, target }
+		} else {
+			match card {
+				Card::Ambassador => History::ChallengeAmbassador { by, target },
+				Card::Inquisitor => History::ChallengeInquisitor { by, target },
+				Card::Assassin => History::ChallengeAssassin { by, target },
+				Card::Captain => History::ChallengeCaptain { by, target },
+				Card::Contessa => History::ChallengeContessa { by, target },
+				Card::Duke => History::ChallengeDuke { by, target },
+			}
 		});
 
 		let player = self.get_bot_by_name(player.clone());
 		let challenger = self.get_bot_by_name(challenger.clone());
 
-		let card = match action {
-			Action::Assassination(_) => Card::Assassin,
-			Action::Swapping => Card::Ambassador,
-			Action::Stealing(_) => Card::Captain,
-			Action::Tax => Card::Duke,
-			Action::Coup(_) | Action::Income | Action::ForeignAid => {
-				unreachable!("Can't challenge Coup, Income or ForeignAid")
-			},
+		let has_card = if player.cards.contains(&card) {
+			"did have"
+		} else {
+			"didn't have"
 		};
 
-		if player.cards.contains(&card) {
-			Self::log(
-				format_args!(
-					"👎  The challenge was unsuccessful because {} \x1b[33mdid have the {:?}\x1b[39m",
-					player, card
-				),
-				self.log,
-			);
-			self.card_loss(challenger.name.clone());
+		// Embezzling is claiming to NOT have the Duke
+		if player.cards.contains(&card) != disclaimed {
+			self.log(format_args!(
+					"👎  The challenge was unsuccessful because {} \x1b[33m{} the {:?}\x1b[39m",
+					player, has_card, card
+				));
+			self.card_loss(challenger.name.clone(), Some(player.name.clone()));
 			false
 		} else {
-			Self::log(
-				format_args!(
-					"👍  The challenge was successful because {} \x1b[33mdidn't have the {:?}\x1b[39m",
-					player, card
-				),
-				self.log,
-			);
-			self.card_loss(player.name.clone());
+			self.log(format_args!(
+					"👍  The challenge was successful because {} \x1b[33m{} the {:?}\x1b[39m",
+					player, has_card, card
+				));
+			self.card_loss(player.name.clone(), Some(challenger.name.clone()));
 			true
 		}
 	}
@@ -1051,33 +2039,34 @@ impl Coup {
 	// A bot is countering another bots action against them
 	fn resolve_counter_challenge(
 		&mut self,
-		counter: Counter,
+		action: &Action,
 		counterer: String,
 		challenger: String,
 	) -> bool {
-		self.history.push(match counter {
-			Counter::Assassination => History::CounterChallengeContessa {
-				by: challenger.clone(),
-				target: counterer.clone(),
-			},
-			Counter::ForeignAid => History::CounterChallengeDuke {
-				by: challenger.clone(),
-				target: counterer.clone(),
-			},
-			Counter::Stealing => History::CounterChallengeCaptainAmbassedor {
-				by: challenger.clone(),
-				target: counterer.clone(),
+		let cards = self.roles.blockers(action);
+
+		// The counter is challenged for the blocking card the bot has or, if it has
+		// none, the first card that could have blocked the action
+		let card = cards
+			.iter()
+			.find(|card| self.get_bot_by_name(counterer.clone()).cards.contains(card))
+			.or(cards.first())
+			.copied()
+			.expect("Can't counter actions that can't be blocked");
+		let by = challenger.clone();
+		let target = counterer.clone();
+		self.push_history(match card {
+			Card::Contessa => History::CounterChallengeContessa { by, target },
+			Card::Duke => History::CounterChallengeDuke { by, target },
+			Card::Ambassador | Card::Captain | Card::Inquisitor => {
+				History::CounterChallengeCaptainAmbassedor { by, target }
 			},
+			Card::Assassin => History::CounterChallengeAssassin { by, target },
 		});
 
 		let counterer = self.get_bot_by_name(counterer.clone());
 		let challenger = self.get_bot_by_name(challenger.clone());
 
-		let cards = match counter {
-			Counter::Assassination => vec![Card::Contessa],
-			Counter::ForeignAid => vec![Card::Duke],
-			Counter::Stealing => vec![Card::Captain, Card::Ambassador],
-		};
 		let card_string = cards
 			.iter()
 			.map(|card| format!("{:?}", card))
@@ -1085,31 +2074,27 @@ impl Coup {
 			.join(" or the ");
 
 		if cards.iter().any(|&card| counterer.cards.contains(&card)) {
-			Self::log(
-				format_args!(
+			self.log(format_args!(
 					"👎  The counter was unsuccessful because {} \x1b[33mdid have the {}\x1b[39m",
 					counterer, card_string
-				),
-				self.log,
-			);
-			self.card_loss(challenger.name.clone());
+				));
+			self.card_loss(challenger.name.clone(), Some(counterer.name.clone()));
 			false
 		} else {
-			Self::log(
-				format_args!(
+			self.log(format_args!(
 					"👍  The counter was successful because {} \x1b[33mdidn't have the {}\x1b[39m",
 					counterer, card_string
-				),
-				self.log,
-			);
-			self.card_loss(counterer.name.clone());
+				));
+			self.card_loss(counterer.name.clone(), Some(challenger.name.clone()));
 			true
 		}
 	}
 
 	fn display_score(&mut self) {
 		let fps = (self.rounds as f64 / 1000.0).max(1.0) as u64;
-		if self.round == 0 || self.round % fps == 0 || self.round + 1 == self.rounds
+		if self.round == 0
+			|| self.round.is_multiple_of(fps)
+			|| self.round + 1 == self.rounds
 		{
 			if self.round > 0 {
 				print!("\x1b[{}A\x1b[2K", self.score.len() + 1);
@@ -1118,10 +2103,10 @@ impl Coup {
 			let done =
 				(((self.round + 1) as f64 / self.rounds as f64) * 100.0).round();
 			println!("\x1b[2K {:>3}% done", done);
-			self.score.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap());
+			self.score.sort_by(|(_, a), (_, b)| b.cmp(a));
 			self.score.iter().for_each(|(name, score)| {
 				let percentage = if self.round > 0 {
-					format!("{:.3}", (score * 100.0) / self.round as f64)
+					format!("{:.3}", (score.to_f64() * 100.0) / self.round as f64)
 				} else { String::from("0") };
 				println!("\x1b[2K\x1b[90m {:>8}%\x1b[39m  \x1b[31m{:>15.5}\x1b[39m  \x1b[33m{}\x1b[39m", percentage, score, name);
 			});
@@ -1149,7 +2134,9 @@ impl Coup {
 
 	/// Play n number of rounds and tally up the score in the CLI.
 	pub fn looping(&mut self, rounds: u64) {
-		self.setup();
+		if let Some(bluff_report) = &mut self.bluff_report {
+			*bluff_report = BluffReport::default();
+		}
 		self.log = false;
 		self.rounds = rounds;
 
@@ -1169,32 +2156,158 @@ impl Coup {
 
 		println!(" ╔═════════════════╗\n ║ 🎲🎲 \x1b[1mBOARD\x1b[0m 🎲🎲 ║\n ╚═════════════════╝\x1b[?25l");
 		self.display_score();
+		// Games won and played by each bot with a handicap
+		let mut handicap_record: Vec<(String, u64, u64)> = Vec::new();
+		// Sum of placements and games played by each bot
+		let mut placement_record: Vec<(String, u64, u64)> = Vec::new();
+		// Games written to the capture folder and the last error writing one
+		let mut captured: u64 = 0;
+		let mut capture_error = None;
 		for round in 0..rounds {
-			self.setup();
-			self.play();
-			// TODO: detect "stop" and record log in debug mode
+			let (result, capture) = self.play_captured(round);
+			match capture {
+				Some(Ok(_)) => captured += 1,
+				Some(Err(error)) => capture_error = Some(error),
+				None => {},
+			}
+			// A game that panicked doesn't count
+			let Some(result) = result else {
+				self.round = round + 1;
+				self.display_score();
+				continue;
+			};
+			if let (Some(bluff_report), Some(claims)) =
+				(&mut self.bluff_report, &self.claims)
+			{
+				bluff_report.add_game(&result.seated(), &self.history, claims);
+			}
+			for (name, placement) in result.placements() {
+				match placement_record.iter_mut().find(|(bot, _, _)| *bot == name) {
+					Some((_, placements, games)) => {
+						*placements += placement as u64;
+						*games += 1;
+					},
+					None => placement_record.push((name, placement as u64, 1)),
+				}
+			}
+			for (name, _) in result.handicaps.iter() {
+				let won = result.winners.contains(name) as u64;
+				match handicap_record.iter_mut().find(|(bot, _, _)| bot == name) {
+					Some((_, wins, games)) => {
+						*wins += won;
+						*games += 1;
+					},
+					None => handicap_record.push((name.clone(), won, 1)),
+				}
+			}
 			self.round = round + 1;
 			self.display_score();
 		}
 
 		println!(
 			"\x1b[?25h\n 🎉🎉🎉 The winner is: \x1b[1m{}\x1b[0m\n",
-			self
-				.score
-				.iter()
-				.max_by(|(_, a), (_, b)| a
-					.partial_cmp(b)
-					.unwrap_or(std::cmp::Ordering::Equal))
-				.unwrap()
-				.0
+			self.score.iter().max_by_key(|(_, score)| *score).unwrap().0
 		);
+
+		placement_record.sort_by(|(_, a_sum, a_games), (_, b_sum, b_games)| {
+			(*a_sum as f64 / *a_games as f64)
+				.total_cmp(&(*b_sum as f64 / *b_games as f64))
+		});
+		println!(" 📊 Average placement");
+		for (name, placements, games) in placement_record {
+			println!(
+				"\x1b[90m {:>8.3}\x1b[39m  \x1b[33m{}\x1b[39m",
+				placements as f64 / games as f64,
+				name
+			);
+		}
+		println!();
+		if let Some(bluff_report) = &self.bluff_report {
+			println!("{}", bluff_report);
+		}
+
+		for (name, wins, games) in handicap_record {
+			println!(
+				" 🏋️  \x1b[1m{}\x1b[0m won \x1b[36m{}\x1b[39m of {} games with a handicap",
+				name,
+				Self::format_number_with_separator(wins),
+				Self::format_number_with_separator(games)
+			);
+		}
+
+		if let Some(capture) = &self.capture {
+			println!(
+				" 🐞 Captured \x1b[36m{}\x1b[39m games to {}",
+				Self::format_number_with_separator(captured),
+				capture.folder.display()
+			);
+			if let Some(error) = capture_error {
+				println!(" 🐞 Could not capture all games: {}", error);
+			}
+		}
+	}
+
+	// Play a game and write it to the capture folder if it stopped at the move
+	// cap, penalized a bot, panicked or matches the capture predicate
+	fn play_captured(
+		&mut self,
+		round: u64,
+	) -> (Option<GameResult>, Option<io::Result<PathBuf>>) {
+		if self.capture.is_none() {
+			return (Some(self.play()), None);
+		}
+
+		let outcome = panic::catch_unwind(AssertUnwindSafe(|| self.play()));
+		let Some(capture) = &self.capture else {
+			return (outcome.ok(), None);
+		};
+		let reason = match &outcome {
+			Err(payload) => Some(CaptureReason::Panic(
+				payload
+					.downcast_ref::<&str>()
+					.map(|message| message.to_string())
+					.or_else(|| payload.downcast_ref::<String>().cloned())
+					.unwrap_or_default(),
+			)),
+			Ok(result) if result.stalemate == Some(Stalemate::MaxMoves) => {
+				Some(CaptureReason::MaxMoves)
+			},
+			Ok(_) if self.penalties > 0 => Some(CaptureReason::Penalty),
+			Ok(result)
+				if capture
+					.predicate
+					.as_ref()
+					.is_some_and(|predicate| predicate(result, &self.history)) =>
+			{
+				Some(CaptureReason::Predicate)
+			},
+			Ok(_) => None,
+		};
+
+		let written = reason.map(|reason| {
+			let transcript = self
+				.transcript
+				.as_ref()
+				.map(|transcript| transcript.borrow().clone())
+				.unwrap_or_default();
+			capture.write(
+				round,
+				&reason,
+				&self.replay(),
+				&self.starting_hands,
+				&transcript,
+				self.audit(),
+			)
+		});
+		(outcome.ok(), written)
 	}
 
 	// *******************************| Actions |****************************** //
 	fn action_assassination(&mut self, target: String) {
 		let playing_bot_coins = self.bots[self.playing_bots[self.turn]].coins;
 		let playing_bot_name = self.bots[self.playing_bots[self.turn]].name.clone();
-		if playing_bot_coins < 3 {
+		let fee = self.roles.cost(&Action::Assassination(target.clone()));
+		if playing_bot_coins < fee {
 			self.penalize_bot(
 				playing_bot_name.clone(),
 				"it tried to assassinate someone with insufficient funds",
@@ -1206,10 +2319,27 @@ impl Coup {
 			);
 		} else {
 			// Paying the fee
-			self.bots[self.playing_bots[self.turn]].coins = playing_bot_coins - 3;
+			self.bots[self.playing_bots[self.turn]].coins = playing_bot_coins - fee;
 
 			// Taking a card from the target bot
-			self.card_loss(target);
+			self.card_loss(target, Some(playing_bot_name));
+		}
+	}
+
+	// Pay the coins the role of an action costs.
+	// Returns false and penalizes the bot if it can't afford it.
+	fn pay_role_cost(&mut self, action: &Action, attempt: &str) -> bool {
+		let coins = self.bots[self.playing_bots[self.turn]].coins;
+		let fee = self.roles.cost(action);
+		if coins < fee {
+			self.penalize_bot(
+				self.bots[self.playing_bots[self.turn]].name.clone(),
+				&format!("it tried to {} with insufficient funds", attempt),
+			);
+			false
+		} else {
+			self.bots[self.playing_bots[self.turn]].coins = coins - fee;
+			true
 		}
 	}
 
@@ -1231,16 +2361,24 @@ impl Coup {
 			self.bots[self.playing_bots[self.turn]].coins = playing_bot_coins - 7;
 
 			// Taking a card from the target bot
-			self.card_loss(target);
+			self.card_loss(target, Some(playing_bot_name));
 		}
 	}
 
 	fn action_foraign_aid(&mut self) {
 		let coins = self.bots[self.playing_bots[self.turn]].coins;
-		self.bots[self.playing_bots[self.turn]].coins = coins + 2;
+		self.bots[self.playing_bots[self.turn]].coins = coins.saturating_add(2);
 	}
 
 	fn action_swapping(&mut self) {
+		if !self.pay_role_cost(&Action::Swapping, "swap cards") {
+			return;
+		}
+		if self.roles.exchange_cards() == 1 {
+			self.action_swapping_single();
+			return;
+		}
+
 		let mut all_available_cards =
 			self.bots[self.playing_bots[self.turn]].cards.clone();
 		let card1 = self.deck.pop().unwrap();
@@ -1249,7 +2387,9 @@ impl Coup {
 		let swapped_cards =
 			self.bots[self.playing_bots[self.turn]].interface.on_swapping_cards(
 				cards_from_deck,
-				&self.get_context(self.bots[self.playing_bots[self.turn]].name.clone()),
+				&self.callback_context(
+					self.bots[self.playing_bots[self.turn]].name.clone(),
+				),
 			);
 		all_available_cards.push(card1);
 		all_available_cards.push(card2);
@@ -1264,7 +2404,19 @@ impl Coup {
 		} else {
 			self.deck.push(swapped_cards[0]);
 			self.deck.push(swapped_cards[1]);
-			self.deck.shuffle(&mut thread_rng());
+			self.record_audit(AuditEvent::Exchange {
+				by: self.bots[self.playing_bots[self.turn]].name.clone(),
+				drawn: cards_from_deck.to_vec(),
+				returned: swapped_cards.to_vec(),
+			});
+			self.shuffle_deck();
+			for card in swapped_cards {
+				self.remember_card(
+					self.playing_bots[self.turn],
+					card,
+					KnownCardSource::Returned,
+				);
+			}
 
 			// removing the discarded cards from the pool and giving it to the bot
 			if let Some(index) =
@@ -1281,35 +2433,173 @@ impl Coup {
 		}
 	}
 
-	fn action_income(&mut self) {
-		let playing_bot_coins = self.bots[self.playing_bots[self.turn]].coins;
-		self.bots[self.playing_bots[self.turn]].coins = playing_bot_coins + 1;
-	}
+	// Roles like the Inquisitor only exchange a single card with the deck
+	fn action_swapping_single(&mut self) {
+		let mut all_available_cards =
+			self.bots[self.playing_bots[self.turn]].cards.clone();
+		let card_from_deck = self.deck.pop().unwrap();
+		let swapped_card =
+			self.bots[self.playing_bots[self.turn]].interface.on_swapping_card(
+				card_from_deck,
+				&self.callback_context(
+					self.bots[self.playing_bots[self.turn]].name.clone(),
+				),
+			);
+		all_available_cards.push(card_from_deck);
 
-	fn action_stealing(&mut self, target: String) {
-		let coins = self.bots[self.playing_bots[self.turn]].coins;
-		let target_coins = self.get_bot_by_name(target.clone()).coins;
-		let booty = std::cmp::min(target_coins, 2);
-		self.bots[self.playing_bots[self.turn]].coins = coins + booty;
-		self
-			.bots
-			.iter_mut()
-			.find(|bot| bot.name.clone() == target)
-			.unwrap()
-			.coins = target_coins - booty;
-	}
+		if !all_available_cards.contains(&swapped_card) {
+			self.penalize_bot(
+				self.bots[self.playing_bots[self.turn]].name.clone(),
+				"it tried to swap a card it didn't have",
+			);
+		} else {
+			self.deck.push(swapped_card);
+			self.record_audit(AuditEvent::Exchange {
+				by: self.bots[self.playing_bots[self.turn]].name.clone(),
+				drawn: vec![card_from_deck],
+				returned: vec![swapped_card],
+			});
+			self.shuffle_deck();
+			self.remember_card(
+				self.playing_bots[self.turn],
+				swapped_card,
+				KnownCardSource::Returned,
+			);
 
-	fn action_tax(&mut self) {
-		let coins = self.bots[self.playing_bots[self.turn]].coins;
-		self.bots[self.playing_bots[self.turn]].coins = coins + 3;
+			// removing the discarded card from the pool and giving it to the bot
+			if let Some(index) =
+				all_available_cards.iter().position(|&c| c == swapped_card)
+			{
+				all_available_cards.remove(index);
+			}
+			self.bots[self.playing_bots[self.turn]].cards = all_available_cards;
+		}
 	}
-}
 
-/// The debug trait has been implemented to support both format and alternate
-/// format which means you can print a game instance with:
-/// ```rust
-/// # use coup::Coup;
-/// let mut my_coup = Coup::new(vec![]);
+	fn action_examine(&mut self, target: String) {
+		let playing_bot_name = self.bots[self.playing_bots[self.turn]].name.clone();
+		if self.get_bot_by_name(target.clone()).cards.is_empty() {
+			// The target may have died in the challenge round
+			return;
+		}
+		if !self.pay_role_cost(&Action::Examine(target.clone()), "examine someone")
+		{
+			return;
+		}
+
+		// The target decides which card it shows
+		let target_bot = self.get_bot_by_name(target.clone());
+		let shown_card = target_bot.interface.on_examine(
+			playing_bot_name.clone(),
+			&self.callback_context(target.clone()),
+		);
+
+		if !target_bot.cards.contains(&shown_card) {
+			self.penalize_bot(
+				target.clone(),
+				"it showed a card it didn't have to the Inquisitor",
+			);
+			return;
+		}
+
+		self.remember_card(
+			self.playing_bots[self.turn],
+			shown_card,
+			KnownCardSource::Examined {
+				owner: target.clone(),
+			},
+		);
+
+		// The examiner decides if the target has to swap the shown card
+		let force_swap =
+			self.bots[self.playing_bots[self.turn]].interface.on_examined_card(
+				shown_card,
+				target.clone(),
+				&self.callback_context(playing_bot_name.clone()),
+			);
+
+		if force_swap {
+			self.push_history(History::ActionExamineSwap {
+				by: playing_bot_name,
+				target: target.clone(),
+			});
+			self.swap_card(shown_card, target);
+		}
+	}
+
+	fn action_income(&mut self) {
+		let playing_bot_coins = self.bots[self.playing_bots[self.turn]].coins;
+		self.bots[self.playing_bots[self.turn]].coins =
+			playing_bot_coins.saturating_add(1);
+	}
+
+	fn action_stealing(&mut self, target: String) {
+		if !self.pay_role_cost(&Action::Stealing(target.clone()), "steal") {
+			return;
+		}
+		let coins = self.bots[self.playing_bots[self.turn]].coins;
+		let target_coins = self.get_bot_by_name(target.clone()).coins;
+		let booty = std::cmp::min(target_coins, 2);
+		self.bots[self.playing_bots[self.turn]].coins = coins.saturating_add(booty);
+		self
+			.bots
+			.iter_mut()
+			.find(|bot| bot.name.clone() == target)
+			.unwrap()
+			.coins = target_coins - booty;
+	}
+
+	fn action_tax(&mut self) {
+		if !self.pay_role_cost(&Action::Tax, "take tax") {
+			return;
+		}
+		let coins = self.bots[self.playing_bots[self.turn]].coins;
+		self.bots[self.playing_bots[self.turn]].coins = coins.saturating_add(3);
+	}
+
+	fn action_conversion(&mut self, target: String) {
+		let playing_bot_coins = self.bots[self.playing_bots[self.turn]].coins;
+		let playing_bot_name = self.bots[self.playing_bots[self.turn]].name.clone();
+		// Converting yourself is cheaper than converting someone else
+		let fee = if target == playing_bot_name { 1 } else { 2 };
+		if playing_bot_coins < fee {
+			self.penalize_bot(
+				playing_bot_name.clone(),
+				"it tried to convert someone with insufficient funds",
+			);
+		} else if self.target_not_found(target.clone()) {
+			self.penalize_bot(
+				playing_bot_name.clone(),
+				"it tried to convert an unknown bot",
+			);
+		} else {
+			// Paying the fee into the treasury reserve which, like the coins of a
+			// bot, stops growing once it can't hold more
+			self.bots[self.playing_bots[self.turn]].coins = playing_bot_coins - fee;
+			self.treasury_reserve = self.treasury_reserve.saturating_add(fee);
+
+			let bot = self.bots.iter_mut().find(|bot| bot.name == target).unwrap();
+			bot.allegiance = match bot.allegiance {
+				Some(Allegiance::Loyalist) => Some(Allegiance::Reformist),
+				Some(Allegiance::Reformist) => Some(Allegiance::Loyalist),
+				None => None,
+			};
+		}
+	}
+
+	fn action_embezzlement(&mut self) {
+		let coins = self.bots[self.playing_bots[self.turn]].coins;
+		self.bots[self.playing_bots[self.turn]].coins =
+			coins.saturating_add(self.treasury_reserve);
+		self.treasury_reserve = 0;
+	}
+}
+
+/// The debug trait has been implemented to support both format and alternate
+/// format which means you can print a game instance with:
+/// ```rust
+/// # use coup::Coup;
+/// let mut my_coup = Coup::new(vec![]);
 /// println!("{:?}", my_coup);
 /// ```
 /// and
@@ -1342,7 +2632,14 @@ impl fmt::Debug for Coup {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::bots::StaticBot;
+	use crate::{
+		belief::Belief,
+		bots::{HonestBot, RandomBot, StaticBot},
+		capture::Capture,
+		replay::{Decision, ReplayError},
+		state::PublicState,
+		viewer::Viewer,
+	};
 
 	#[test]
 	fn test_new() {
@@ -1357,8 +2654,8 @@ mod tests {
 		assert_eq!(
 			coup.score,
 			vec![
-				(String::from("StaticBot"), 0.0),
-				(String::from("StaticBot 2"), 0.0)
+				(String::from("StaticBot"), Points::ZERO),
+				(String::from("StaticBot 2"), Points::ZERO)
 			]
 		);
 		assert_eq!(coup.turn, 0);
@@ -1379,6 +2676,50 @@ mod tests {
 		assert_eq!(deck.iter().filter(|&card| card == &Card::Duke).count(), 3);
 	}
 
+	#[test]
+	fn test_new_deck_with() {
+		let deck = Coup::new_deck_with(Deck::Classic, 6);
+		assert_eq!(deck.len(), 15);
+		assert_eq!(
+			deck.iter().filter(|&card| card == &Card::Ambassador).count(),
+			3
+		);
+		assert_eq!(
+			deck.iter().filter(|&card| card == &Card::Inquisitor).count(),
+			0
+		);
+
+		let deck = Coup::new_deck_with(Deck::Inquisitor, 6);
+		assert_eq!(deck.len(), 15);
+		assert_eq!(
+			deck.iter().filter(|&card| card == &Card::Ambassador).count(),
+			0
+		);
+		assert_eq!(
+			deck.iter().filter(|&card| card == &Card::Inquisitor).count(),
+			3
+		);
+		assert_eq!(deck.iter().filter(|&card| card == &Card::Assassin).count(), 3);
+		assert_eq!(deck.iter().filter(|&card| card == &Card::Captain).count(), 3);
+		assert_eq!(deck.iter().filter(|&card| card == &Card::Contessa).count(), 3);
+		assert_eq!(deck.iter().filter(|&card| card == &Card::Duke).count(), 3);
+
+		let deck = Coup::new_deck_with(Deck::Classic, 2);
+		assert_eq!(deck.len(), 15);
+		let deck = Coup::new_deck_with(Deck::Classic, 7);
+		assert_eq!(deck.len(), 20);
+		assert_eq!(deck.iter().filter(|&card| card == &Card::Duke).count(), 4);
+		let deck = Coup::new_deck_with(Deck::Inquisitor, 8);
+		assert_eq!(deck.len(), 20);
+		assert_eq!(
+			deck.iter().filter(|&card| card == &Card::Inquisitor).count(),
+			4
+		);
+		let deck = Coup::new_deck_with(Deck::Classic, 10);
+		assert_eq!(deck.len(), 25);
+		assert_eq!(deck.iter().filter(|&card| card == &Card::Contessa).count(), 5);
+	}
+
 	#[test]
 	fn test_setup() {
 		let mut coup = Coup::new(vec![
@@ -1412,114 +2753,590 @@ mod tests {
 		assert_eq!(coup.moves, 0);
 	}
 
-	// TODO: test_log
+	#[test]
+	fn test_setup_two_player() {
+		struct TestBot;
+		impl BotInterface for TestBot {
+			fn get_name(&self) -> String {
+				String::from("TestBot")
+			}
+			fn on_choose_starting_card(
+				&self,
+				cards: Vec<Card>,
+				_context: &Context,
+			) -> Card {
+				assert_eq!(
+					cards,
+					vec![
+						Card::Ambassador,
+						Card::Assassin,
+						Card::Captain,
+						Card::Contessa,
+						Card::Duke
+					]
+				);
+				Card::Duke
+			}
+		}
+
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(TestBot), Box::new(TestBot), Box::new(TestBot)],
+			Config {
+				two_player: true,
+				..Config::default()
+			},
+		);
+		coup.setup();
+
+		assert_eq!(coup.playing_bots.len(), 2);
+		assert_eq!(coup.bots[coup.playing_bots[0]].coins, 1);
+		assert_eq!(coup.bots[coup.playing_bots[0]].cards.len(), 2);
+		assert_eq!(coup.bots[coup.playing_bots[0]].cards[0], Card::Duke);
+		assert_eq!(coup.bots[coup.playing_bots[1]].coins, 2);
+		assert_eq!(coup.bots[coup.playing_bots[1]].cards.len(), 2);
+		assert_eq!(coup.bots[coup.playing_bots[1]].cards[0], Card::Duke);
+		// 10 cards were offered, 8 of them set aside and 2 cards were dealt
+		assert_eq!(coup.deck.len(), 3);
+		assert_eq!(
+			coup.bots[coup.playing_bots[0]]
+				.known_cards
+				.iter()
+				.filter(|known| known.source == KnownCardSource::SetAside)
+				.count(),
+			4
+		);
+		assert_eq!(coup.discard_pile, vec![]);
+		assert_eq!(coup.turn, 0);
+		assert_eq!(coup.moves, 0);
+	}
 
 	#[test]
-	fn test_get_bot_by_name() {
-		let mut coup = Coup::new(vec![Box::new(StaticBot), Box::new(StaticBot)]);
+	fn test_setup_two_player_faulty_bot() {
+		struct TestBot;
+		impl BotInterface for TestBot {
+			fn get_name(&self) -> String {
+				String::from("TestBot")
+			}
+			fn on_choose_starting_card(
+				&self,
+				_cards: Vec<Card>,
+				_context: &Context,
+			) -> Card {
+				Card::Inquisitor
+			}
+		}
+
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(TestBot), Box::new(StaticBot)],
+			Config {
+				two_player: true,
+				..Config::default()
+			},
+		);
+		coup.setup();
+
+		assert_eq!(coup.get_bot_by_name(String::from("TestBot")).cards.len(), 1);
+		assert_eq!(coup.get_bot_by_name(String::from("StaticBot")).cards.len(), 2);
+		assert_eq!(coup.discard_pile.len(), 1);
+		assert_eq!(coup.deck.len(), 3);
+	}
+
+	#[test]
+	fn test_setup_draft() {
+		struct TestBot;
+		impl BotInterface for TestBot {
+			fn get_name(&self) -> String {
+				String::from("TestBot")
+			}
+			fn on_draft(&self, cards: Vec<Card>, _context: &Context) -> [Card; 2] {
+				assert_eq!(cards.len(), 4);
+				[cards[3], cards[1]]
+			}
+		}
+
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(TestBot), Box::new(TestBot), Box::new(TestBot)],
+			Config {
+				draft: Some(4),
+				..Config::default()
+			},
+		);
 		coup.setup();
 
+		for bot in coup.playing_bots.iter() {
+			assert_eq!(coup.bots[*bot].cards.len(), 2);
+			assert_eq!(coup.bots[*bot].coins, 2);
+		}
+		assert_eq!(coup.deck.len(), 9);
 		assert_eq!(
-			coup.get_bot_by_name(String::from("StaticBot")).name,
-			String::from("StaticBot")
+			coup.history,
+			coup
+				.playing_bots
+				.iter()
+				.map(|bot| History::Draft {
+					by: coup.bots[*bot].name.clone()
+				})
+				.collect::<Vec<History>>()
 		);
 		assert_eq!(
-			coup.get_bot_by_name(String::from("StaticBot 2")).name,
-			String::from("StaticBot 2")
+			coup.starting_hands,
+			coup
+				.playing_bots
+				.iter()
+				.map(|bot| (
+					coup.bots[*bot].name.clone(),
+					coup.bots[*bot].cards.clone()
+				))
+				.collect::<Vec<(String, Vec<Card>)>>()
 		);
 	}
 
 	#[test]
-	fn test_get_other_bots() {
-		let mut coup = Coup::new(vec![
-			Box::new(StaticBot),
-			Box::new(StaticBot),
-			Box::new(StaticBot),
-			Box::new(StaticBot),
-			Box::new(StaticBot),
-		]);
+	fn test_setup_draft_faulty_bot() {
+		struct TestBot;
+		impl BotInterface for TestBot {
+			fn get_name(&self) -> String {
+				String::from("TestBot")
+			}
+			fn on_draft(&self, _cards: Vec<Card>, _context: &Context) -> [Card; 2] {
+				[Card::Inquisitor, Card::Inquisitor]
+			}
+		}
+
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(TestBot), Box::new(StaticBot)],
+			Config {
+				draft: Some(3),
+				..Config::default()
+			},
+		);
 		coup.setup();
 
-		coup.playing_bots = vec![0, 1, 2, 3, 4];
-		coup.turn = 0;
-		assert!(coup.get_other_bots().contains(&OtherBot {
-			name: String::from("StaticBot"),
-			coins: 2,
-			cards: 2
-		}));
-		assert!(coup.get_other_bots().contains(&OtherBot {
-			name: String::from("StaticBot 2"),
-			coins: 2,
-			cards: 2
-		}));
-		assert!(coup.get_other_bots().contains(&OtherBot {
-			name: String::from("StaticBot 3"),
-			coins: 2,
-			cards: 2
-		}));
-		assert!(coup.get_other_bots().contains(&OtherBot {
-			name: String::from("StaticBot 4"),
-			coins: 2,
-			cards: 2
-		}));
-		assert!(coup.get_other_bots().contains(&OtherBot {
-			name: String::from("StaticBot 5"),
-			coins: 2,
-			cards: 2
-		}));
+		assert_eq!(coup.get_bot_by_name(String::from("TestBot")).cards.len(), 1);
+		assert_eq!(coup.get_bot_by_name(String::from("StaticBot")).cards.len(), 2);
+		assert_eq!(coup.discard_pile.len(), 1);
+		assert_eq!(coup.deck.len(), 11);
+	}
 
-		coup.playing_bots = vec![4, 3, 2, 1, 0];
-		assert!(coup.get_other_bots().contains(&OtherBot {
-			name: String::from("StaticBot"),
-			coins: 2,
-			cards: 2
-		}));
-		assert!(coup.get_other_bots().contains(&OtherBot {
-			name: String::from("StaticBot 2"),
-			coins: 2,
-			cards: 2
-		}));
-		assert!(coup.get_other_bots().contains(&OtherBot {
-			name: String::from("StaticBot 3"),
-			coins: 2,
-			cards: 2
-		}));
-		assert!(coup.get_other_bots().contains(&OtherBot {
-			name: String::from("StaticBot 4"),
-			coins: 2,
-			cards: 2
-		}));
-		assert!(coup.get_other_bots().contains(&OtherBot {
-			name: String::from("StaticBot 5"),
-			coins: 2,
-			cards: 2
-		}));
+	#[test]
+	#[should_panic(
+		expected = "Bots can be shown between 2 and 5 cards to draft from"
+	)]
+	fn test_new_with_config_draft_too_big() {
+		Coup::new_with_config(
+			vec![Box::new(StaticBot), Box::new(StaticBot)],
+			Config {
+				draft: Some(6),
+				..Config::default()
+			},
+		);
+	}
 
-		coup.turn = 2;
+	#[test]
+	fn test_setup_handicaps() {
+		let mut coup = Coup::new_with_config(
+			vec![
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+			],
+			Config {
+				handicaps: Some(vec![
+					Handicap { coins: 0, cards: 1 },
+					Handicap::default(),
+					Handicap { coins: 5, cards: 2 },
+				]),
+				..Config::default()
+			},
+		);
+		coup.setup();
+
+		assert_eq!(coup.bots[0].coins, 0);
+		assert_eq!(coup.bots[0].cards.len(), 1);
+		assert_eq!(coup.bots[0].handicap, Some(Handicap { coins: 0, cards: 1 }));
+		assert_eq!(coup.bots[1].coins, 2);
+		assert_eq!(coup.bots[1].cards.len(), 2);
+		assert_eq!(coup.bots[1].handicap, None);
+		assert_eq!(coup.bots[2].coins, 5);
+		assert_eq!(coup.bots[2].cards.len(), 2);
+		assert_eq!(coup.deck.len(), 10);
+		assert_eq!(format!("{}", coup.bots[0]).matches('♡').count(), 0);
 		assert!(coup.get_other_bots().contains(&OtherBot {
 			name: String::from("StaticBot"),
-			coins: 2,
-			cards: 2
-		}));
-		assert!(coup.get_other_bots().contains(&OtherBot {
-			name: String::from("StaticBot 2"),
-			coins: 2,
-			cards: 2
-		}));
-		assert!(coup.get_other_bots().contains(&OtherBot {
-			name: String::from("StaticBot 3"),
-			coins: 2,
-			cards: 2
-		}));
-		assert!(coup.get_other_bots().contains(&OtherBot {
-			name: String::from("StaticBot 4"),
-			coins: 2,
-			cards: 2
+			coins: 0,
+			cards: 1,
+			allegiance: None,
+			team: None,
+			handicap: Some(Handicap { coins: 0, cards: 1 }),
+		}));
+
+		coup.log = false;
+		let result = coup.play();
+		assert_eq!(result.handicaps.len(), 2);
+		assert!(result
+			.handicaps
+			.contains(&(String::from("StaticBot"), Handicap { coins: 0, cards: 1 })));
+		assert!(result.handicaps.contains(&(
+			String::from("StaticBot 3"),
+			Handicap { coins: 5, cards: 2 }
+		)));
+	}
+
+	#[test]
+	#[should_panic(expected = "Bots can only start with 1 or 2 cards")]
+	fn test_new_with_config_handicap_without_cards() {
+		Coup::new_with_config(
+			vec![Box::new(StaticBot), Box::new(StaticBot)],
+			Config {
+				handicaps: Some(vec![
+					Handicap { coins: 2, cards: 0 },
+					Handicap::default(),
+				]),
+				..Config::default()
+			},
+		);
+	}
+
+	#[test]
+	fn test_stalemate() {
+		struct StealAndBlockBot;
+		impl BotInterface for StealAndBlockBot {
+			fn get_name(&self) -> String {
+				String::from("StealAndBlockBot")
+			}
+			fn on_turn(&self, context: &Context) -> Action {
+				Action::Stealing(context.targets()[0].name.clone())
+			}
+			fn on_counter(
+				&self,
+				_action: &Action,
+				_by: String,
+				_context: &Context,
+			) -> bool {
+				true
+			}
+		}
+
+		// The same state comes up again every round
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(StealAndBlockBot), Box::new(StealAndBlockBot)],
+			Config {
+				stalemate_repetitions: Some(3),
+				..Config::default()
+			},
+		);
+		coup.log = false;
+		let result = coup.play();
+		assert_eq!(result.stalemate, Some(Stalemate::Repetition));
+		assert_eq!(result.winners.len(), 2);
+		assert_eq!(result.moves, 5);
+		assert_eq!(result.tie_breaker, Some(TieBreaker::Shared));
+
+		// Nothing changes over two rounds
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(StealAndBlockBot), Box::new(StealAndBlockBot)],
+			Config {
+				stalemate_repetitions: None,
+				stalemate_rounds: Some(2),
+				..Config::default()
+			},
+		);
+		coup.log = false;
+		let result = coup.play();
+		assert_eq!(result.stalemate, Some(Stalemate::NoProgress));
+		assert_eq!(result.moves, 6);
+
+		// By default only the move cap stops the game
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(StealAndBlockBot), Box::new(StealAndBlockBot)],
+			Config {
+				max_moves: 10,
+				..Config::default()
+			},
+		);
+		coup.log = false;
+		let result = coup.play();
+		assert_eq!(result.stalemate, Some(Stalemate::MaxMoves));
+		assert_eq!(result.moves, 10);
+
+		// Bots who take income progress
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(StaticBot), Box::new(StaticBot)],
+			Config {
+				stalemate_repetitions: Some(3),
+				stalemate_rounds: Some(20),
+				..Config::default()
+			},
+		);
+		coup.log = false;
+		let result = coup.play();
+		assert_eq!(result.stalemate, None);
+		assert_eq!(result.winners.len(), 1);
+	}
+
+	#[test]
+	fn test_break_tie() {
+		let mut coup = Coup::new_with_config(
+			vec![
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+			],
+			Config {
+				tie_breakers: vec![TieBreaker::MostInfluence, TieBreaker::MostCoins],
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.bots[0].cards = vec![Card::Duke, Card::Duke];
+		coup.bots[1].cards = vec![Card::Duke, Card::Duke];
+		coup.bots[2].cards = vec![Card::Duke];
+
+		// Most coins decides between the bots with most influence
+		coup.bots[0].coins = 3;
+		coup.bots[1].coins = 5;
+		coup.bots[2].coins = 9;
+		assert_eq!(coup.break_tie(vec![0, 1, 2]), (vec![1], TieBreaker::MostCoins));
+
+		// Most influence alone decides
+		coup.bots[1].cards = vec![Card::Duke];
+		assert_eq!(
+			coup.break_tie(vec![0, 1, 2]),
+			(vec![0], TieBreaker::MostInfluence)
+		);
+
+		// Nothing decides
+		coup.bots[1].cards = vec![Card::Duke, Card::Duke];
+		coup.bots[1].coins = 3;
+		assert_eq!(coup.break_tie(vec![0, 1, 2]), (vec![0, 1], TieBreaker::Shared));
+
+		// Without tie-breakers all bots share the win
+		coup.config.tie_breakers = vec![];
+		assert_eq!(
+			coup.break_tie(vec![0, 1, 2]),
+			(vec![0, 1, 2], TieBreaker::Shared)
+		);
+
+		// A team with the most influence wins together
+		let mut coup = Coup::new_with_config(
+			vec![
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+			],
+			Config {
+				teams: Some(vec![0, 0, 1]),
+				tie_breakers: vec![TieBreaker::MostInfluence, TieBreaker::MostCoins],
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.bots[0].cards = vec![Card::Duke, Card::Duke];
+		coup.bots[1].cards = vec![Card::Duke, Card::Duke];
+		coup.bots[2].cards = vec![Card::Duke];
+		assert_eq!(
+			coup.break_tie(vec![0, 1, 2]),
+			(vec![0, 1], TieBreaker::MostInfluence)
+		);
+	}
+
+	#[test]
+	fn test_setup_ten_players() {
+		let mut coup = Coup::new_with_config(
+			vec![
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+			],
+			Config {
+				max_players: 10,
+				..Config::default()
+			},
+		);
+		coup.setup();
+
+		assert_eq!(coup.playing_bots.len(), 10);
+		assert_eq!(coup.seated_bots, coup.playing_bots);
+		for bot in coup.playing_bots.iter() {
+			assert_eq!(coup.bots[*bot].cards.len(), 2);
+			assert_eq!(coup.bots[*bot].coins, 2);
+		}
+		assert_eq!(coup.deck.len(), 5);
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_new_with_config_too_many_players() {
+		Coup::new_with_config(
+			vec![Box::new(StaticBot), Box::new(StaticBot)],
+			Config {
+				max_players: 11,
+				..Config::default()
+			},
+		);
+	}
+
+	// TODO: test_log
+
+	#[test]
+	fn test_get_bot_by_name() {
+		let mut coup = Coup::new(vec![Box::new(StaticBot), Box::new(StaticBot)]);
+		coup.setup();
+
+		assert_eq!(
+			coup.get_bot_by_name(String::from("StaticBot")).name,
+			String::from("StaticBot")
+		);
+		assert_eq!(
+			coup.get_bot_by_name(String::from("StaticBot 2")).name,
+			String::from("StaticBot 2")
+		);
+	}
+
+	#[test]
+	fn test_get_other_bots() {
+		let mut coup = Coup::new(vec![
+			Box::new(StaticBot),
+			Box::new(StaticBot),
+			Box::new(StaticBot),
+			Box::new(StaticBot),
+			Box::new(StaticBot),
+		]);
+		coup.setup();
+
+		coup.playing_bots = vec![0, 1, 2, 3, 4];
+		coup.turn = 0;
+		assert!(coup.get_other_bots().contains(&OtherBot {
+			name: String::from("StaticBot"),
+			coins: 2,
+			cards: 2,
+			allegiance: None,
+			team: None,
+			handicap: None,
+		}));
+		assert!(coup.get_other_bots().contains(&OtherBot {
+			name: String::from("StaticBot 2"),
+			coins: 2,
+			cards: 2,
+			allegiance: None,
+			team: None,
+			handicap: None,
+		}));
+		assert!(coup.get_other_bots().contains(&OtherBot {
+			name: String::from("StaticBot 3"),
+			coins: 2,
+			cards: 2,
+			allegiance: None,
+			team: None,
+			handicap: None,
+		}));
+		assert!(coup.get_other_bots().contains(&OtherBot {
+			name: String::from("StaticBot 4"),
+			coins: 2,
+			cards: 2,
+			allegiance: None,
+			team: None,
+			handicap: None,
+		}));
+		assert!(coup.get_other_bots().contains(&OtherBot {
+			name: String::from("StaticBot 5"),
+			coins: 2,
+			cards: 2,
+			allegiance: None,
+			team: None,
+			handicap: None,
+		}));
+
+		coup.playing_bots = vec![4, 3, 2, 1, 0];
+		assert!(coup.get_other_bots().contains(&OtherBot {
+			name: String::from("StaticBot"),
+			coins: 2,
+			cards: 2,
+			allegiance: None,
+			team: None,
+			handicap: None,
+		}));
+		assert!(coup.get_other_bots().contains(&OtherBot {
+			name: String::from("StaticBot 2"),
+			coins: 2,
+			cards: 2,
+			allegiance: None,
+			team: None,
+			handicap: None,
+		}));
+		assert!(coup.get_other_bots().contains(&OtherBot {
+			name: String::from("StaticBot 3"),
+			coins: 2,
+			cards: 2,
+			allegiance: None,
+			team: None,
+			handicap: None,
+		}));
+		assert!(coup.get_other_bots().contains(&OtherBot {
+			name: String::from("StaticBot 4"),
+			coins: 2,
+			cards: 2,
+			allegiance: None,
+			team: None,
+			handicap: None,
+		}));
+		assert!(coup.get_other_bots().contains(&OtherBot {
+			name: String::from("StaticBot 5"),
+			coins: 2,
+			cards: 2,
+			allegiance: None,
+			team: None,
+			handicap: None,
+		}));
+
+		coup.turn = 2;
+		assert!(coup.get_other_bots().contains(&OtherBot {
+			name: String::from("StaticBot"),
+			coins: 2,
+			cards: 2,
+			allegiance: None,
+			team: None,
+			handicap: None,
+		}));
+		assert!(coup.get_other_bots().contains(&OtherBot {
+			name: String::from("StaticBot 2"),
+			coins: 2,
+			cards: 2,
+			allegiance: None,
+			team: None,
+			handicap: None,
+		}));
+		assert!(coup.get_other_bots().contains(&OtherBot {
+			name: String::from("StaticBot 3"),
+			coins: 2,
+			cards: 2,
+			allegiance: None,
+			team: None,
+			handicap: None,
+		}));
+		assert!(coup.get_other_bots().contains(&OtherBot {
+			name: String::from("StaticBot 4"),
+			coins: 2,
+			cards: 2,
+			allegiance: None,
+			team: None,
+			handicap: None,
 		}));
 		assert!(coup.get_other_bots().contains(&OtherBot {
 			name: String::from("StaticBot 5"),
 			coins: 2,
-			cards: 2
+			cards: 2,
+			allegiance: None,
+			team: None,
+			handicap: None,
 		}));
 
 		coup.bots[0].cards = vec![];
@@ -1531,11 +3348,17 @@ mod tests {
 			name: String::from("StaticBot 2"),
 			coins: 2,
 			cards: 1,
+			allegiance: None,
+			team: None,
+			handicap: None,
 		}));
 		assert!(coup.get_other_bots().contains(&OtherBot {
 			name: String::from("StaticBot 3"),
 			coins: 2,
 			cards: 2,
+			allegiance: None,
+			team: None,
+			handicap: None,
 		}));
 	}
 
@@ -1547,9 +3370,11 @@ mod tests {
 		coup.bots[0].cards = vec![Card::Ambassador, Card::Duke];
 		coup.bots[1].cards = vec![Card::Captain, Card::Captain];
 		coup.playing_bots = vec![0, 1];
+		coup.seated_bots = vec![0, 1];
 
+		let context = coup.get_context(String::from("StaticBot"));
 		assert_eq!(
-			coup.get_context(String::from("StaticBot")),
+			context,
 			Context {
 				name: String::from("StaticBot"),
 				coins: 2,
@@ -1558,26 +3383,47 @@ mod tests {
 					OtherBot {
 						name: String::from("StaticBot"),
 						coins: 2,
-						cards: 2
+						cards: 2,
+						allegiance: None,
+						team: None,
+						handicap: None,
 					},
 					OtherBot {
 						name: String::from("StaticBot 2"),
 						coins: 2,
-						cards: 2
+						cards: 2,
+						allegiance: None,
+						team: None,
+						handicap: None,
 					}
 				],
 				discard_pile: vec![],
 				history: vec![],
 				score: vec![
-					(String::from("StaticBot"), 0.0),
-					(String::from("StaticBot 2"), 0.0)
+					(String::from("StaticBot"), Points::ZERO),
+					(String::from("StaticBot 2"), Points::ZERO)
 				],
+				treasury_reserve: 0,
+				friendly_fire: false,
+				roles: RoleSet::classic(),
+				seats: vec![String::from("StaticBot"), String::from("StaticBot 2")],
+				turn: 0,
+				moves: 0,
+				max_moves: 1000,
+				deck_size: 11,
+				acting_bot: String::from("StaticBot"),
+				pending_turn: None,
+				known_cards: vec![],
+				seed: context.seed,
+				rng: Default::default(),
 			}
 		);
 
 		coup.turn = 1;
+		coup.moves = 7;
+		let context = coup.get_context(String::from("StaticBot 2"));
 		assert_eq!(
-			coup.get_context(String::from("StaticBot 2")),
+			context,
 			Context {
 				name: String::from("StaticBot 2"),
 				coins: 2,
@@ -1586,24 +3432,90 @@ mod tests {
 					OtherBot {
 						name: String::from("StaticBot"),
 						coins: 2,
-						cards: 2
+						cards: 2,
+						allegiance: None,
+						team: None,
+						handicap: None,
 					},
 					OtherBot {
 						name: String::from("StaticBot 2"),
 						coins: 2,
-						cards: 2
+						cards: 2,
+						allegiance: None,
+						team: None,
+						handicap: None,
 					}
 				],
 				discard_pile: vec![],
 				history: vec![],
 				score: vec![
-					(String::from("StaticBot"), 0.0),
-					(String::from("StaticBot 2"), 0.0)
+					(String::from("StaticBot"), Points::ZERO),
+					(String::from("StaticBot 2"), Points::ZERO)
 				],
+				treasury_reserve: 0,
+				friendly_fire: false,
+				roles: RoleSet::classic(),
+				seats: vec![String::from("StaticBot"), String::from("StaticBot 2")],
+				turn: 1,
+				moves: 7,
+				max_moves: 1000,
+				deck_size: 11,
+				acting_bot: String::from("StaticBot 2"),
+				pending_turn: None,
+				known_cards: vec![],
+				seed: context.seed,
+				rng: Default::default(),
 			}
 		);
 	}
 
+	#[test]
+	fn test_context_seed() {
+		let coup = Coup::new_with_config(
+			vec![Box::new(StaticBot), Box::new(StaticBot)],
+			Config {
+				seed: Some(42),
+				..Config::default()
+			},
+		);
+		// Looking at a context doesn't touch the random stream of the bot
+		let peeked = coup.get_context(String::from("StaticBot")).seed;
+		assert_eq!(coup.get_context(String::from("StaticBot")).seed, peeked);
+
+		// Each callback draws the next number
+		let first = coup.callback_context(String::from("StaticBot")).seed;
+		let second = coup.callback_context(String::from("StaticBot")).seed;
+		let other = coup.callback_context(String::from("StaticBot 2")).seed;
+		assert_eq!(first, peeked);
+		assert_ne!(first, second);
+		assert_ne!(first, other);
+
+		// The rng of a context continues its stream with each call
+		let context = coup.callback_context(String::from("StaticBot"));
+		let draws = (context.rng().gen::<u64>(), context.rng().gen::<u64>());
+		assert_ne!(draws.0, draws.1);
+		assert_eq!(draws, {
+			let mut rng = StdRng::seed_from_u64(context.seed);
+			(rng.gen::<u64>(), rng.gen::<u64>())
+		});
+
+		// Using the stream doesn't change what a context compares as
+		let untouched = context.clone();
+		context.rng().gen::<u64>();
+		assert_eq!(context, untouched);
+
+		// A context can be built outside of a game
+		let context = Context {
+			seed: draws.0,
+			..Context::default()
+		};
+		assert_eq!(context.roles, RoleSet::classic());
+		assert_eq!(context.rng().gen::<u64>(), {
+			let mut rng = StdRng::seed_from_u64(draws.0);
+			rng.gen::<u64>()
+		});
+	}
+
 	#[test]
 	fn test_card_loss() {
 		let mut coup = Coup::new(vec![Box::new(StaticBot), Box::new(StaticBot)]);
@@ -1612,7 +3524,7 @@ mod tests {
 		coup.bots[0].cards = vec![Card::Ambassador, Card::Duke];
 		coup.bots[1].cards = vec![Card::Captain, Card::Captain];
 
-		coup.card_loss(String::from("StaticBot 2"));
+		coup.card_loss(String::from("StaticBot 2"), None);
 
 		assert_eq!(coup.bots[0].cards, vec![Card::Ambassador, Card::Duke]);
 		assert_eq!(coup.bots[1].cards, vec![Card::Captain]);
@@ -1637,7 +3549,7 @@ mod tests {
 		coup.bots[0].cards = vec![Card::Ambassador, Card::Duke];
 		coup.bots[1].cards = vec![Card::Assassin, Card::Captain];
 
-		coup.card_loss(String::from("TestBot"));
+		coup.card_loss(String::from("TestBot"), None);
 
 		assert_eq!(coup.bots[0].cards, vec![Card::Ambassador, Card::Duke]);
 		assert_eq!(coup.bots[1].cards, vec![]);
@@ -1651,9 +3563,9 @@ mod tests {
 		let mut coup = Coup::new(vec![Box::new(StaticBot), Box::new(StaticBot)]);
 		coup.setup();
 
-		assert_eq!(coup.target_not_found(String::from("StaticBot")), false);
-		assert_eq!(coup.target_not_found(String::from("StaticBot 3")), true);
-		assert_eq!(coup.target_not_found(String::from("StaticBot 2")), false);
+		assert!(!coup.target_not_found(String::from("StaticBot")));
+		assert!(coup.target_not_found(String::from("StaticBot 3")));
+		assert!(!coup.target_not_found(String::from("StaticBot 2")));
 
 		let mut coup = Coup::new(vec![
 			Box::new(StaticBot),
@@ -1667,11 +3579,153 @@ mod tests {
 		coup.setup();
 		coup.playing_bots = vec![0, 1, 2, 3, 4, 5];
 
-		assert_eq!(coup.target_not_found(String::from("StaticBot 7")), true);
+		assert!(coup.target_not_found(String::from("StaticBot 7")));
 
 		coup.playing_bots = vec![1, 2, 3, 4, 5, 6];
 
-		assert_eq!(coup.target_not_found(String::from("StaticBot 7")), false);
+		assert!(!coup.target_not_found(String::from("StaticBot 7")));
+	}
+
+	#[test]
+	fn test_target_same_faction() {
+		let mut coup = Coup::new_with_config(
+			vec![
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+			],
+			Config {
+				reformation: true,
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.playing_bots = vec![0, 1, 2];
+		coup.bots[0].allegiance = Some(Allegiance::Loyalist);
+		coup.bots[1].allegiance = Some(Allegiance::Loyalist);
+		coup.bots[2].allegiance = Some(Allegiance::Reformist);
+
+		assert!(coup.target_same_faction(
+			String::from("StaticBot"),
+			String::from("StaticBot 2")
+		));
+		assert!(!coup.target_same_faction(
+			String::from("StaticBot"),
+			String::from("StaticBot 3")
+		));
+
+		// Once the other faction is gone everyone can be targeted again
+		coup.bots[2].cards = vec![];
+		assert!(!coup.target_same_faction(
+			String::from("StaticBot"),
+			String::from("StaticBot 2")
+		));
+
+		// Without the Reformation expansion there are no factions
+		let mut coup = Coup::new(vec![Box::new(StaticBot), Box::new(StaticBot)]);
+		coup.setup();
+		assert!(!coup.target_same_faction(
+			String::from("StaticBot"),
+			String::from("StaticBot 2")
+		));
+	}
+
+	#[test]
+	fn test_target_teammate() {
+		let mut coup = Coup::new_with_config(
+			vec![
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+			],
+			Config {
+				teams: Some(vec![0, 0, 1]),
+				..Config::default()
+			},
+		);
+		coup.setup();
+
+		assert_eq!(coup.bots[0].team, Some(0));
+		assert_eq!(coup.bots[2].team, Some(1));
+		assert!(coup
+			.target_teammate(String::from("StaticBot"), String::from("StaticBot 2")));
+		assert!(!coup
+			.target_teammate(String::from("StaticBot"), String::from("StaticBot 3")));
+		assert_eq!(
+			coup.target_protected(
+				String::from("StaticBot 2"),
+				String::from("StaticBot")
+			),
+			Some("a teammate")
+		);
+
+		// Without anyone left to target the default auto coup picks a teammate
+		coup.playing_bots = vec![0, 1];
+		let context = coup.get_context(String::from("StaticBot"));
+		assert!(context.targets().is_empty());
+		assert_eq!(StaticBot.on_auto_coup(&context), String::from("StaticBot 2"));
+
+		// Friendly fire allows targeting teammates
+		coup.config.friendly_fire = true;
+		assert!(!coup
+			.target_teammate(String::from("StaticBot"), String::from("StaticBot 2")));
+
+		// Without teams nobody is a teammate
+		let mut coup = Coup::new(vec![Box::new(StaticBot), Box::new(StaticBot)]);
+		coup.setup();
+		assert!(!coup
+			.target_teammate(String::from("StaticBot"), String::from("StaticBot 2")));
+	}
+
+	#[test]
+	#[should_panic(expected = "Each bot needs to be assigned to a team")]
+	fn test_new_with_config_missing_team() {
+		Coup::new_with_config(
+			vec![Box::new(StaticBot), Box::new(StaticBot)],
+			Config {
+				teams: Some(vec![0]),
+				..Config::default()
+			},
+		);
+	}
+
+	#[test]
+	fn test_game_over() {
+		let mut coup = Coup::new_with_config(
+			vec![
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+			],
+			Config {
+				teams: Some(vec![0, 0, 1]),
+				..Config::default()
+			},
+		);
+		coup.setup();
+
+		coup.playing_bots = vec![0, 1, 2];
+		assert!(!coup.game_over());
+
+		coup.playing_bots = vec![0, 1];
+		assert!(coup.game_over());
+
+		coup.playing_bots = vec![2];
+		assert!(coup.game_over());
+
+		// Without teams only a single bot wins
+		let mut coup = Coup::new(vec![
+			Box::new(StaticBot),
+			Box::new(StaticBot),
+			Box::new(StaticBot),
+		]);
+		coup.setup();
+
+		coup.playing_bots = vec![0, 1];
+		assert!(!coup.game_over());
+
+		coup.playing_bots = vec![0];
+		assert!(coup.game_over());
 	}
 
 	#[test]
@@ -1680,13 +3734,17 @@ mod tests {
 		let mut coup = Coup::new(vec![Box::new(StaticBot), Box::new(StaticBot)]);
 		coup.setup();
 
-		coup.set_score(vec![String::from("StaticBot")]);
+		coup.set_score(&coup.game_result(
+			vec![String::from("StaticBot")],
+			None,
+			None,
+		));
 
 		assert_eq!(
 			coup.score,
 			vec![
-				(String::from("StaticBot"), 1.0),
-				(String::from("StaticBot 2"), -1.0)
+				(String::from("StaticBot"), Points::from(1)),
+				(String::from("StaticBot 2"), Points::from(-1))
 			]
 		);
 
@@ -1700,16 +3758,20 @@ mod tests {
 		]);
 		coup.setup();
 
-		coup.set_score(vec![String::from("StaticBot")]);
+		coup.set_score(&coup.game_result(
+			vec![String::from("StaticBot")],
+			None,
+			None,
+		));
 
 		assert_eq!(
 			coup.score,
 			vec![
-				(String::from("StaticBot"), 1.0),
-				(String::from("StaticBot 2"), -0.25),
-				(String::from("StaticBot 3"), -0.25),
-				(String::from("StaticBot 4"), -0.25),
-				(String::from("StaticBot 5"), -0.25),
+				(String::from("StaticBot"), Points::from(1)),
+				(String::from("StaticBot 2"), Points::new(-1, 4)),
+				(String::from("StaticBot 3"), Points::new(-1, 4)),
+				(String::from("StaticBot 4"), Points::new(-1, 4)),
+				(String::from("StaticBot 5"), Points::new(-1, 4)),
 			]
 		);
 
@@ -1723,28 +3785,252 @@ mod tests {
 		]);
 		coup.setup();
 
-		coup
-			.set_score(vec![String::from("StaticBot"), String::from("StaticBot 2")]);
+		coup.set_score(&coup.game_result(
+			vec![String::from("StaticBot"), String::from("StaticBot 2")],
+			None,
+			None,
+		));
 
 		assert_eq!(
 			coup.score,
 			vec![
-				(String::from("StaticBot"), 0.5),
-				(String::from("StaticBot 2"), 0.5),
-				(String::from("StaticBot 3"), -0.3333333333333333),
-				(String::from("StaticBot 4"), -0.3333333333333333),
-				(String::from("StaticBot 5"), -0.3333333333333333),
+				(String::from("StaticBot"), Points::new(1, 2)),
+				(String::from("StaticBot 2"), Points::new(1, 2)),
+				(String::from("StaticBot 3"), Points::new(-1, 3)),
+				(String::from("StaticBot 4"), Points::new(-1, 3)),
+				(String::from("StaticBot 5"), Points::new(-1, 3)),
 			]
 		);
-	}
-
-	#[test]
-	fn test_swap_card() {
-		let mut coup = Coup::new(vec![Box::new(StaticBot), Box::new(StaticBot)]);
-		coup.setup();
-		coup.playing_bots = vec![0, 1];
 
-		coup.bots[0].cards = vec![Card::Ambassador, Card::Ambassador];
+		// Eight bots, six seated, one winner
+		coup = Coup::new(vec![
+			Box::new(StaticBot),
+			Box::new(StaticBot),
+			Box::new(StaticBot),
+			Box::new(StaticBot),
+			Box::new(StaticBot),
+			Box::new(StaticBot),
+			Box::new(StaticBot),
+			Box::new(StaticBot),
+		]);
+		coup.setup();
+		coup.seated_bots = vec![0, 1, 2, 3, 4, 5];
+		coup.starting_hands = coup.bots[..6]
+			.iter()
+			.map(|bot| (bot.name.clone(), bot.cards.clone()))
+			.collect();
+
+		coup.set_score(&coup.game_result(
+			vec![String::from("StaticBot")],
+			None,
+			None,
+		));
+
+		assert_eq!(
+			coup.score,
+			vec![
+				(String::from("StaticBot"), Points::from(1)),
+				(String::from("StaticBot 2"), Points::new(-1, 5)),
+				(String::from("StaticBot 3"), Points::new(-1, 5)),
+				(String::from("StaticBot 4"), Points::new(-1, 5)),
+				(String::from("StaticBot 5"), Points::new(-1, 5)),
+				(String::from("StaticBot 6"), Points::new(-1, 5)),
+				(String::from("StaticBot 7"), Points::ZERO),
+				(String::from("StaticBot 8"), Points::ZERO),
+			]
+		);
+
+		// Two teams of two and a single bot, one team wins
+		coup = Coup::new_with_config(
+			vec![
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+			],
+			Config {
+				teams: Some(vec![0, 0, 1, 1, 2]),
+				..Config::default()
+			},
+		);
+		coup.setup();
+
+		coup.set_score(&coup.game_result(
+			vec![String::from("StaticBot"), String::from("StaticBot 2")],
+			None,
+			None,
+		));
+
+		assert_eq!(
+			coup.score,
+			vec![
+				(String::from("StaticBot"), Points::new(1, 2)),
+				(String::from("StaticBot 2"), Points::new(1, 2)),
+				(String::from("StaticBot 3"), Points::new(-1, 4)),
+				(String::from("StaticBot 4"), Points::new(-1, 4)),
+				(String::from("StaticBot 5"), Points::new(-1, 2)),
+			]
+		);
+
+		// Everyone still in the game
+		coup = Coup::new(vec![Box::new(StaticBot), Box::new(StaticBot)]);
+		coup.setup();
+
+		coup.set_score(&coup.game_result(
+			vec![String::from("StaticBot"), String::from("StaticBot 2")],
+			None,
+			None,
+		));
+
+		assert_eq!(
+			coup.score,
+			vec![
+				(String::from("StaticBot"), Points::ZERO),
+				(String::from("StaticBot 2"), Points::ZERO)
+			]
+		);
+	}
+
+	#[test]
+	fn test_scoring_rules() {
+		use crate::scoring::{Placement, Survival, WinLoss};
+
+		let result = GameResult {
+			winners: vec![String::from("A")],
+			moves: 20,
+			stalemate: None,
+			tie_breaker: None,
+			starting_hands: vec![
+				(String::from("A"), vec![]),
+				(String::from("B"), vec![]),
+				(String::from("C"), vec![]),
+			],
+			handicaps: vec![],
+			teams: vec![],
+			eliminations: vec![
+				Elimination {
+					name: String::from("C"),
+					by: Some(String::from("A")),
+					turn: 5,
+				},
+				Elimination {
+					name: String::from("B"),
+					by: None,
+					turn: 20,
+				},
+			],
+		};
+
+		assert_eq!(
+			ZeroSum.score(&result),
+			vec![
+				(String::from("A"), Points::from(1)),
+				(String::from("B"), Points::new(-1, 2)),
+				(String::from("C"), Points::new(-1, 2)),
+			]
+		);
+		assert_eq!(
+			result.placements(),
+			vec![
+				(String::from("A"), 1),
+				(String::from("B"), 2),
+				(String::from("C"), 3),
+			]
+		);
+		assert_eq!(
+			Placement.score(&result),
+			vec![
+				(String::from("A"), Points::from(1)),
+				(String::from("B"), Points::new(1, 2)),
+				(String::from("C"), Points::ZERO),
+			]
+		);
+		assert_eq!(
+			WinLoss.score(&result),
+			vec![
+				(String::from("A"), Points::from(1)),
+				(String::from("B"), Points::from(-1)),
+				(String::from("C"), Points::from(-1)),
+			]
+		);
+		assert_eq!(
+			Survival.score(&result),
+			vec![
+				(String::from("A"), Points::from(20)),
+				(String::from("B"), Points::from(20)),
+				(String::from("C"), Points::from(5)),
+			]
+		);
+
+		// Teams are scored as one side
+		let result = GameResult {
+			teams: vec![
+				(String::from("A"), 0),
+				(String::from("B"), 0),
+				(String::from("C"), 1),
+			],
+			..result
+		};
+		assert_eq!(
+			ZeroSum.score(&result),
+			vec![
+				(String::from("A"), Points::new(1, 2)),
+				(String::from("B"), Points::new(1, 2)),
+				(String::from("C"), Points::from(-1)),
+			]
+		);
+	}
+
+	#[test]
+	fn test_points() {
+		assert_eq!(Points::new(2, 4), Points::new(1, 2));
+		assert_eq!(Points::new(1, -3), Points::new(-1, 3));
+		assert_eq!(Points::new(-2, -6).numerator(), 1);
+		assert_eq!(Points::new(-2, -6).denominator(), 3);
+		assert_eq!(Points::new(1, 3) + Points::new(1, 6), Points::new(1, 2));
+		assert_eq!(-Points::new(1, 3), Points::new(-1, 3));
+		assert!(Points::new(1, 3) > Points::new(1, 4));
+		assert!(Points::new(-1, 3) < Points::ZERO);
+		assert_eq!(Points::new(1, 4).to_f64(), 0.25);
+		assert_eq!(format!("{:.2}", Points::new(1, 3)), "0.33");
+
+		// A million games add up exactly
+		let mut total = Points::ZERO;
+		for _ in 0..1_000_000 {
+			total += Points::new(-1, 5);
+			total += Points::new(1, 5);
+		}
+		assert_eq!(total, Points::ZERO);
+	}
+
+	#[test]
+	fn test_set_scoring_rule() {
+		let mut coup = Coup::new(vec![Box::new(StaticBot), Box::new(StaticBot)]);
+		coup.log = false;
+		coup.set_scoring_rule(crate::scoring::WinLoss);
+
+		let result = coup.play();
+
+		assert_eq!(result.eliminations.len(), 1);
+		assert!(!result.winners.contains(&result.eliminations[0].name));
+		assert_eq!(result.eliminations[0].by, Some(result.winners[0].clone()));
+		for (name, score) in coup.score.iter() {
+			if result.winners.contains(name) {
+				assert_eq!(*score, Points::from(1));
+			} else {
+				assert_eq!(*score, Points::from(-1));
+			}
+		}
+	}
+
+	#[test]
+	fn test_swap_card() {
+		let mut coup = Coup::new(vec![Box::new(StaticBot), Box::new(StaticBot)]);
+		coup.setup();
+		coup.playing_bots = vec![0, 1];
+
+		coup.bots[0].cards = vec![Card::Ambassador, Card::Ambassador];
 		coup.bots[1].cards = vec![Card::Assassin, Card::Captain];
 		coup.deck = vec![Card::Ambassador, Card::Captain];
 
@@ -1753,15 +4039,36 @@ mod tests {
 		assert_eq!(coup.deck, vec![Card::Ambassador, Card::Captain]);
 		assert_eq!(coup.discard_pile, vec![]);
 
+		coup.moves = 4;
 		coup.swap_card(Card::Ambassador, String::from("StaticBot"));
 
 		assert_eq!(coup.bots[0].cards.len(), 2);
 		assert_eq!(coup.bots[1].cards, vec![Card::Assassin, Card::Captain]);
 		assert_eq!(coup.deck.len(), 2);
 		assert_eq!(coup.discard_pile, vec![]);
+		assert_eq!(
+			coup.bots[0].known_cards,
+			vec![KnownCard {
+				card: Card::Ambassador,
+				source: KnownCardSource::Shuffled,
+				turn: 4,
+			}]
+		);
+		assert_eq!(coup.bots[1].known_cards, vec![]);
 	}
 
-	// TODO: test_play
+	#[test]
+	fn test_play() {
+		let mut coup = Coup::new(vec![Box::new(StaticBot), Box::new(StaticBot)]);
+		coup.log = false;
+
+		let result = coup.play();
+
+		assert_eq!(result.winners.len(), 1);
+		assert_eq!(result.moves, coup.moves);
+		assert_eq!(result.starting_hands.len(), 2);
+		assert_eq!(result.starting_hands[0].1.len(), 2);
+	}
 
 	#[test]
 	fn test_game_loop() {
@@ -1902,11 +4209,15 @@ mod tests {
 			vec![
 				History::ActionAssassination {
 					by: String::from("AssassinationBot"),
-					target: String::from("StaticBot"),
+					target: String::from("StaticBot")
 				},
 				History::ChallengeAssassin {
 					by: String::from("ActionChallengeBot"),
-					target: String::from("AssassinationBot"),
+					target: String::from("AssassinationBot")
+				},
+				History::CardLoss {
+					by: String::from("AssassinationBot"),
+					card: Card::Captain
 				}
 			]
 		);
@@ -1921,6 +4232,7 @@ mod tests {
 			Box::new(StaticBot),
 		]);
 		coup.setup();
+		coup.bots[1].cards = vec![Card::Contessa, Card::Duke];
 		coup.bots[0].cards = vec![Card::Duke, Card::Captain];
 		coup.bots[0].coins = 4;
 		coup.bots[2].cards = vec![Card::Ambassador, Card::Assassin];
@@ -1943,11 +4255,15 @@ mod tests {
 			vec![
 				History::ActionStealing {
 					by: String::from("StealingBot"),
-					target: String::from("StaticBot"),
+					target: String::from("StaticBot")
 				},
 				History::ChallengeCaptain {
 					by: String::from("ActionChallengeBot"),
-					target: String::from("StealingBot"),
+					target: String::from("StealingBot")
+				},
+				History::CardLoss {
+					by: String::from("ActionChallengeBot"),
+					card: Card::Duke
 				}
 			]
 		);
@@ -2021,16 +4337,20 @@ mod tests {
 			coup.history,
 			vec![
 				History::ActionForeignAid {
-					by: String::from("ForeignAidBot"),
+					by: String::from("ForeignAidBot")
 				},
 				History::CounterForeignAid {
 					by: String::from("CounterBot"),
-					target: String::from("ForeignAidBot"),
+					target: String::from("ForeignAidBot")
 				},
 				History::CounterChallengeDuke {
 					by: String::from("ChallengeCounterBot"),
-					target: String::from("CounterBot"),
+					target: String::from("CounterBot")
 				},
+				History::CardLoss {
+					by: String::from("CounterBot"),
+					card: Card::Assassin
+				}
 			]
 		);
 
@@ -2047,6 +4367,7 @@ mod tests {
 		coup.bots[0].cards = vec![Card::Duke, Card::Captain];
 		coup.bots[0].coins = 4;
 		coup.bots[1].cards = vec![Card::Duke, Card::Assassin];
+		coup.bots[2].cards = vec![Card::Captain, Card::Contessa];
 		coup.playing_bots = vec![0, 1, 2, 3, 4, 5];
 		coup.turn = 0;
 		coup.history = vec![];
@@ -2064,16 +4385,20 @@ mod tests {
 			coup.history,
 			vec![
 				History::ActionForeignAid {
-					by: String::from("ForeignAidBot"),
+					by: String::from("ForeignAidBot")
 				},
 				History::CounterForeignAid {
 					by: String::from("CounterBot"),
-					target: String::from("ForeignAidBot"),
+					target: String::from("ForeignAidBot")
 				},
 				History::CounterChallengeDuke {
 					by: String::from("ChallengeCounterBot"),
-					target: String::from("CounterBot"),
+					target: String::from("CounterBot")
 				},
+				History::CardLoss {
+					by: String::from("ChallengeCounterBot"),
+					card: Card::Contessa
+				}
 			]
 		);
 
@@ -2105,10 +4430,16 @@ mod tests {
 		assert_eq!(coup.bots[5].cards.len(), 2);
 		assert_eq!(
 			coup.history,
-			vec![History::ActionAssassination {
-				by: String::from("AssassinationBot"),
-				target: String::from("StaticBot"),
-			},]
+			vec![
+				History::ActionAssassination {
+					by: String::from("AssassinationBot"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot"),
+					card: Card::Assassin
+				}
+			]
 		);
 
 		// Coup
@@ -2139,10 +4470,16 @@ mod tests {
 		assert_eq!(coup.bots[5].cards.len(), 2);
 		assert_eq!(
 			coup.history,
-			vec![History::ActionCoup {
-				by: String::from("CoupBot"),
-				target: String::from("StaticBot"),
-			},]
+			vec![
+				History::ActionCoup {
+					by: String::from("CoupBot"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot"),
+					card: Card::Assassin
+				}
+			]
 		);
 
 		// ForeignAid
@@ -2423,10 +4760,16 @@ mod tests {
 		assert_eq!(coup.bots[5].cards.len(), 2);
 		assert_eq!(
 			coup.history,
-			vec![History::ChallengeAssassin {
-				by: String::from("ActionChallengeBot"),
-				target: String::from("StaticBot"),
-			}]
+			vec![
+				History::ChallengeAssassin {
+					by: String::from("ActionChallengeBot"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot"),
+					card: Card::Captain
+				}
+			]
 		);
 
 		// Successful counter
@@ -2500,11 +4843,19 @@ mod tests {
 			vec![
 				History::CounterAssassination {
 					by: String::from("CounterBot"),
-					target: String::from("StaticBot"),
+					target: String::from("StaticBot")
 				},
 				History::CounterChallengeContessa {
 					by: String::from("ChallengeCounterBot"),
-					target: String::from("CounterBot"),
+					target: String::from("CounterBot")
+				},
+				History::CardLoss {
+					by: String::from("CounterBot"),
+					card: Card::Assassin
+				},
+				History::CardLoss {
+					by: String::from("CounterBot"),
+					card: Card::Captain
 				}
 			]
 		);
@@ -2549,6 +4900,7 @@ mod tests {
 			Box::new(StaticBot),
 		]);
 		coup.setup();
+		coup.bots[1].cards = vec![Card::Contessa, Card::Duke];
 		coup.bots[0].cards = vec![Card::Assassin, Card::Captain];
 		coup.bots[0].coins = 4;
 		coup.bots[3].cards = vec![Card::Ambassador, Card::Assassin];
@@ -2570,10 +4922,20 @@ mod tests {
 		assert_eq!(coup.bots[5].cards.len(), 2);
 		assert_eq!(
 			coup.history,
-			vec![History::ChallengeAssassin {
-				by: String::from("ActionChallengeBot"),
-				target: String::from("StaticBot"),
-			}]
+			vec![
+				History::ChallengeAssassin {
+					by: String::from("ActionChallengeBot"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("ActionChallengeBot"),
+					card: Card::Duke
+				},
+				History::CardLoss {
+					by: String::from("StaticBot 3"),
+					card: Card::Assassin
+				}
+			]
 		);
 
 		// Unsuccessful counter challenge
@@ -2611,11 +4973,15 @@ mod tests {
 			vec![
 				History::CounterAssassination {
 					by: String::from("CounterBot"),
-					target: String::from("StaticBot"),
+					target: String::from("StaticBot")
 				},
 				History::CounterChallengeContessa {
 					by: String::from("ChallengeCounterBot"),
-					target: String::from("CounterBot"),
+					target: String::from("CounterBot")
+				},
+				History::CardLoss {
+					by: String::from("ChallengeCounterBot"),
+					card: Card::Ambassador
 				}
 			]
 		);
@@ -2698,10 +5064,16 @@ mod tests {
 		assert_eq!(coup.bots[5].cards.len(), 2);
 		assert_eq!(
 			coup.history,
-			vec![History::ChallengeCaptain {
-				by: String::from("ActionChallengeBot"),
-				target: String::from("StaticBot"),
-			}]
+			vec![
+				History::ChallengeCaptain {
+					by: String::from("ActionChallengeBot"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot"),
+					card: Card::Assassin
+				}
+			]
 		);
 
 		// Successful counter
@@ -2777,11 +5149,15 @@ mod tests {
 			vec![
 				History::CounterStealing {
 					by: String::from("CounterBot"),
-					target: String::from("StaticBot"),
+					target: String::from("StaticBot")
 				},
 				History::CounterChallengeCaptainAmbassedor {
 					by: String::from("ChallengeCounterBot"),
-					target: String::from("CounterBot"),
+					target: String::from("CounterBot")
+				},
+				History::CardLoss {
+					by: String::from("CounterBot"),
+					card: Card::Assassin
 				}
 			]
 		);
@@ -2827,6 +5203,7 @@ mod tests {
 			Box::new(StaticBot),
 		]);
 		coup.setup();
+		coup.bots[1].cards = vec![Card::Contessa, Card::Duke];
 		coup.bots[0].cards = vec![Card::Assassin, Card::Captain];
 		coup.bots[0].coins = 4;
 		coup.bots[3].cards = vec![Card::Duke, Card::Assassin];
@@ -2849,10 +5226,16 @@ mod tests {
 		assert_eq!(coup.bots[5].cards.len(), 2);
 		assert_eq!(
 			coup.history,
-			vec![History::ChallengeCaptain {
-				by: String::from("ActionChallengeBot"),
-				target: String::from("StaticBot"),
-			}]
+			vec![
+				History::ChallengeCaptain {
+					by: String::from("ActionChallengeBot"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("ActionChallengeBot"),
+					card: Card::Duke
+				}
+			]
 		);
 
 		// Unsuccessful counter challenge with Captain
@@ -2891,11 +5274,15 @@ mod tests {
 			vec![
 				History::CounterStealing {
 					by: String::from("CounterBot"),
-					target: String::from("StaticBot"),
+					target: String::from("StaticBot")
 				},
 				History::CounterChallengeCaptainAmbassedor {
 					by: String::from("ChallengeCounterBot"),
-					target: String::from("CounterBot"),
+					target: String::from("CounterBot")
+				},
+				History::CardLoss {
+					by: String::from("ChallengeCounterBot"),
+					card: Card::Ambassador
 				}
 			]
 		);
@@ -2936,11 +5323,15 @@ mod tests {
 			vec![
 				History::CounterStealing {
 					by: String::from("CounterBot"),
-					target: String::from("StaticBot"),
+					target: String::from("StaticBot")
 				},
 				History::CounterChallengeCaptainAmbassedor {
 					by: String::from("ChallengeCounterBot"),
-					target: String::from("CounterBot"),
+					target: String::from("CounterBot")
+				},
+				History::CardLoss {
+					by: String::from("ChallengeCounterBot"),
+					card: Card::Ambassador
 				}
 			]
 		);
@@ -3150,6 +5541,112 @@ mod tests {
 		assert_eq!(coup.bots[4].cards, vec![Card::Captain]);
 	}
 
+	#[test]
+	fn test_pending_turn() {
+		struct CounterBot;
+		impl BotInterface for CounterBot {
+			fn get_name(&self) -> String {
+				String::from("CounterBot")
+			}
+			fn on_counter(
+				&self,
+				_action: &Action,
+				_by: String,
+				context: &Context,
+			) -> bool {
+				// Counterers are asked with their own context
+				assert_eq!(context.name, "CounterBot");
+				assert_eq!(
+					context.pending_turn,
+					Some(PendingTurn {
+						actor: String::from("StaticBot"),
+						action: Action::ForeignAid,
+						target: None,
+						claimed_card: None,
+						counterer: None,
+						challenges: vec![],
+					})
+				);
+				true
+			}
+		}
+		struct ChallengeBot;
+		impl BotInterface for ChallengeBot {
+			fn get_name(&self) -> String {
+				String::from("ChallengeBot")
+			}
+			fn on_challenge_counter_round(
+				&self,
+				_action: &Action,
+				_by: String,
+				context: &Context,
+			) -> bool {
+				assert_eq!(
+					context.pending_turn.as_ref().unwrap().counterer,
+					Some(String::from("CounterBot"))
+				);
+				true
+			}
+		}
+
+		let mut coup = Coup::new(vec![
+			Box::new(StaticBot),
+			Box::new(CounterBot),
+			Box::new(ChallengeBot),
+		]);
+		coup.setup();
+		coup.bots[0].cards = vec![Card::Assassin, Card::Captain];
+		coup.bots[1].cards = vec![Card::Duke, Card::Assassin];
+		coup.bots[2].cards = vec![Card::Captain, Card::Contessa];
+		coup.playing_bots = vec![0, 1, 2];
+		coup.turn = 0;
+
+		coup.counter_round_only();
+
+		assert_eq!(coup.bots[2].cards.len(), 1);
+		assert_eq!(
+			coup.pending_turn,
+			Some(PendingTurn {
+				actor: String::from("StaticBot"),
+				action: Action::ForeignAid,
+				target: None,
+				claimed_card: None,
+				counterer: Some(String::from("CounterBot")),
+				challenges: vec![PendingChallenge {
+					by: String::from("ChallengeBot"),
+					target: String::from("CounterBot"),
+					successful: false,
+				}],
+			})
+		);
+
+		// Targeted actions record their target and the card they claim
+		coup.setup();
+		coup.bots[0].cards = vec![Card::Assassin, Card::Captain];
+		coup.bots[1].cards = vec![Card::Duke, Card::Assassin];
+		coup.bots[2].cards = vec![Card::Captain, Card::Contessa];
+		coup.bots[0].coins = 3;
+		coup.playing_bots = vec![2, 0, 1];
+		coup.turn = 1;
+
+		coup.challenge_and_counter_round(
+			Action::Stealing(String::from("ChallengeBot")),
+			String::from("ChallengeBot"),
+		);
+
+		assert_eq!(
+			coup.pending_turn,
+			Some(PendingTurn {
+				actor: String::from("StaticBot"),
+				action: Action::Stealing(String::from("ChallengeBot")),
+				target: Some(String::from("ChallengeBot")),
+				claimed_card: Some(Card::Captain),
+				counterer: None,
+				challenges: vec![],
+			})
+		);
+	}
+
 	#[test]
 	fn test_challenge_round_action_no_challenge() {
 		struct TestBot {
@@ -3577,15 +6074,21 @@ mod tests {
 			String::from("StaticBot 2"),
 		);
 
-		assert_eq!(result, true);
+		assert!(result);
 		assert_eq!(coup.bots[0].cards, vec![Card::Duke]);
 		assert_eq!(coup.bots[1].cards, vec![Card::Ambassador, Card::Ambassador]);
 		assert_eq!(
 			coup.history,
-			vec![History::ChallengeAssassin {
-				by: String::from("StaticBot 2"),
-				target: String::from("StaticBot")
-			}]
+			vec![
+				History::ChallengeAssassin {
+					by: String::from("StaticBot 2"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot"),
+					card: Card::Captain
+				}
+			]
 		);
 		coup.history = vec![];
 
@@ -3599,15 +6102,21 @@ mod tests {
 			String::from("StaticBot 2"),
 		);
 
-		assert_eq!(result, true);
+		assert!(result);
 		assert_eq!(coup.bots[0].cards, vec![Card::Duke]);
 		assert_eq!(coup.bots[1].cards, vec![Card::Ambassador, Card::Ambassador]);
 		assert_eq!(
 			coup.history,
-			vec![History::ChallengeAmbassador {
-				by: String::from("StaticBot 2"),
-				target: String::from("StaticBot")
-			}]
+			vec![
+				History::ChallengeAmbassador {
+					by: String::from("StaticBot 2"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot"),
+					card: Card::Captain
+				}
+			]
 		);
 		coup.history = vec![];
 
@@ -3621,15 +6130,21 @@ mod tests {
 			String::from("StaticBot 2"),
 		);
 
-		assert_eq!(result, true);
+		assert!(result);
 		assert_eq!(coup.bots[0].cards, vec![Card::Duke]);
 		assert_eq!(coup.bots[1].cards, vec![Card::Ambassador, Card::Ambassador]);
 		assert_eq!(
 			coup.history,
-			vec![History::ChallengeCaptain {
-				by: String::from("StaticBot 2"),
-				target: String::from("StaticBot")
-			}]
+			vec![
+				History::ChallengeCaptain {
+					by: String::from("StaticBot 2"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot"),
+					card: Card::Assassin
+				}
+			]
 		);
 		coup.history = vec![];
 
@@ -3643,15 +6158,21 @@ mod tests {
 			String::from("StaticBot 2"),
 		);
 
-		assert_eq!(result, true);
+		assert!(result);
 		assert_eq!(coup.bots[0].cards, vec![Card::Captain]);
 		assert_eq!(coup.bots[1].cards, vec![Card::Ambassador, Card::Ambassador]);
 		assert_eq!(
 			coup.history,
-			vec![History::ChallengeDuke {
-				by: String::from("StaticBot 2"),
-				target: String::from("StaticBot")
-			}]
+			vec![
+				History::ChallengeDuke {
+					by: String::from("StaticBot 2"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot"),
+					card: Card::Captain
+				}
+			]
 		);
 	}
 
@@ -3670,15 +6191,21 @@ mod tests {
 			String::from("StaticBot 2"),
 		);
 
-		assert_eq!(result, false);
+		assert!(!result);
 		assert_eq!(coup.bots[0].cards, vec![Card::Assassin, Card::Captain]);
 		assert_eq!(coup.bots[1].cards, vec![Card::Ambassador]);
 		assert_eq!(
 			coup.history,
-			vec![History::ChallengeAssassin {
-				by: String::from("StaticBot 2"),
-				target: String::from("StaticBot")
-			}]
+			vec![
+				History::ChallengeAssassin {
+					by: String::from("StaticBot 2"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot 2"),
+					card: Card::Ambassador
+				}
+			]
 		);
 		coup.history = vec![];
 
@@ -3692,15 +6219,21 @@ mod tests {
 			String::from("StaticBot 2"),
 		);
 
-		assert_eq!(result, false);
+		assert!(!result);
 		assert_eq!(coup.bots[0].cards, vec![Card::Assassin, Card::Ambassador]);
 		assert_eq!(coup.bots[1].cards, vec![Card::Ambassador]);
 		assert_eq!(
 			coup.history,
-			vec![History::ChallengeAmbassador {
-				by: String::from("StaticBot 2"),
-				target: String::from("StaticBot")
-			}]
+			vec![
+				History::ChallengeAmbassador {
+					by: String::from("StaticBot 2"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot 2"),
+					card: Card::Ambassador
+				}
+			]
 		);
 		coup.history = vec![];
 
@@ -3714,15 +6247,21 @@ mod tests {
 			String::from("StaticBot 2"),
 		);
 
-		assert_eq!(result, false);
+		assert!(!result);
 		assert_eq!(coup.bots[0].cards, vec![Card::Assassin, Card::Captain]);
 		assert_eq!(coup.bots[1].cards, vec![Card::Ambassador]);
 		assert_eq!(
 			coup.history,
-			vec![History::ChallengeCaptain {
-				by: String::from("StaticBot 2"),
-				target: String::from("StaticBot")
-			}]
+			vec![
+				History::ChallengeCaptain {
+					by: String::from("StaticBot 2"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot 2"),
+					card: Card::Ambassador
+				}
+			]
 		);
 		coup.history = vec![];
 
@@ -3736,15 +6275,21 @@ mod tests {
 			String::from("StaticBot 2"),
 		);
 
-		assert_eq!(result, false);
+		assert!(!result);
 		assert_eq!(coup.bots[0].cards, vec![Card::Duke, Card::Captain]);
 		assert_eq!(coup.bots[1].cards, vec![Card::Ambassador]);
 		assert_eq!(
 			coup.history,
-			vec![History::ChallengeDuke {
-				by: String::from("StaticBot 2"),
-				target: String::from("StaticBot")
-			}]
+			vec![
+				History::ChallengeDuke {
+					by: String::from("StaticBot 2"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot 2"),
+					card: Card::Ambassador
+				}
+			]
 		);
 		coup.history = vec![];
 	}
@@ -3759,20 +6304,26 @@ mod tests {
 		coup.bots[1].cards = vec![Card::Ambassador, Card::Ambassador];
 
 		let result = coup.resolve_counter_challenge(
-			Counter::Assassination,
+			&Action::Assassination(String::from("StaticBot")),
 			String::from("StaticBot"),
 			String::from("StaticBot 2"),
 		);
 
-		assert_eq!(result, true);
+		assert!(result);
 		assert_eq!(coup.bots[0].cards, vec![Card::Duke]);
 		assert_eq!(coup.bots[1].cards, vec![Card::Ambassador, Card::Ambassador]);
 		assert_eq!(
 			coup.history,
-			vec![History::CounterChallengeContessa {
-				by: String::from("StaticBot 2"),
-				target: String::from("StaticBot")
-			}]
+			vec![
+				History::CounterChallengeContessa {
+					by: String::from("StaticBot 2"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot"),
+					card: Card::Captain
+				}
+			]
 		);
 		coup.history = vec![];
 
@@ -3781,20 +6332,26 @@ mod tests {
 		coup.bots[1].cards = vec![Card::Ambassador, Card::Ambassador];
 
 		let result = coup.resolve_counter_challenge(
-			Counter::ForeignAid,
+			&Action::ForeignAid,
 			String::from("StaticBot"),
 			String::from("StaticBot 2"),
 		);
 
-		assert_eq!(result, true);
+		assert!(result);
 		assert_eq!(coup.bots[0].cards, vec![Card::Assassin]);
 		assert_eq!(coup.bots[1].cards, vec![Card::Ambassador, Card::Ambassador]);
 		assert_eq!(
 			coup.history,
-			vec![History::CounterChallengeDuke {
-				by: String::from("StaticBot 2"),
-				target: String::from("StaticBot")
-			}]
+			vec![
+				History::CounterChallengeDuke {
+					by: String::from("StaticBot 2"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot"),
+					card: Card::Captain
+				}
+			]
 		);
 		coup.history = vec![];
 
@@ -3803,20 +6360,26 @@ mod tests {
 		coup.bots[1].cards = vec![Card::Ambassador, Card::Ambassador];
 
 		let result = coup.resolve_counter_challenge(
-			Counter::Stealing,
+			&Action::Stealing(String::from("StaticBot")),
 			String::from("StaticBot"),
 			String::from("StaticBot 2"),
 		);
 
-		assert_eq!(result, true);
+		assert!(result);
 		assert_eq!(coup.bots[0].cards, vec![Card::Assassin]);
 		assert_eq!(coup.bots[1].cards, vec![Card::Ambassador, Card::Ambassador]);
 		assert_eq!(
 			coup.history,
-			vec![History::CounterChallengeCaptainAmbassedor {
-				by: String::from("StaticBot 2"),
-				target: String::from("StaticBot")
-			}]
+			vec![
+				History::CounterChallengeCaptainAmbassedor {
+					by: String::from("StaticBot 2"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot"),
+					card: Card::Contessa
+				}
+			]
 		);
 	}
 
@@ -3830,20 +6393,26 @@ mod tests {
 		coup.bots[1].cards = vec![Card::Ambassador, Card::Ambassador];
 
 		let result = coup.resolve_counter_challenge(
-			Counter::Assassination,
+			&Action::Assassination(String::from("StaticBot")),
 			String::from("StaticBot"),
 			String::from("StaticBot 2"),
 		);
 
-		assert_eq!(result, false);
+		assert!(!result);
 		assert_eq!(coup.bots[0].cards, vec![Card::Assassin, Card::Contessa]);
 		assert_eq!(coup.bots[1].cards, vec![Card::Ambassador]);
 		assert_eq!(
 			coup.history,
-			vec![History::CounterChallengeContessa {
-				by: String::from("StaticBot 2"),
-				target: String::from("StaticBot")
-			}]
+			vec![
+				History::CounterChallengeContessa {
+					by: String::from("StaticBot 2"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot 2"),
+					card: Card::Ambassador
+				}
+			]
 		);
 		coup.history = vec![];
 
@@ -3852,20 +6421,26 @@ mod tests {
 		coup.bots[1].cards = vec![Card::Ambassador, Card::Ambassador];
 
 		let result = coup.resolve_counter_challenge(
-			Counter::ForeignAid,
+			&Action::ForeignAid,
 			String::from("StaticBot"),
 			String::from("StaticBot 2"),
 		);
 
-		assert_eq!(result, false);
+		assert!(!result);
 		assert_eq!(coup.bots[0].cards, vec![Card::Duke, Card::Contessa]);
 		assert_eq!(coup.bots[1].cards, vec![Card::Ambassador]);
 		assert_eq!(
 			coup.history,
-			vec![History::CounterChallengeDuke {
-				by: String::from("StaticBot 2"),
-				target: String::from("StaticBot")
-			}]
+			vec![
+				History::CounterChallengeDuke {
+					by: String::from("StaticBot 2"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot 2"),
+					card: Card::Ambassador
+				}
+			]
 		);
 		coup.history = vec![];
 
@@ -3874,20 +6449,26 @@ mod tests {
 		coup.bots[1].cards = vec![Card::Ambassador, Card::Ambassador];
 
 		let result = coup.resolve_counter_challenge(
-			Counter::Stealing,
+			&Action::Stealing(String::from("StaticBot")),
 			String::from("StaticBot"),
 			String::from("StaticBot 2"),
 		);
 
-		assert_eq!(result, false);
+		assert!(!result);
 		assert_eq!(coup.bots[0].cards, vec![Card::Duke, Card::Captain]);
 		assert_eq!(coup.bots[1].cards, vec![Card::Ambassador]);
 		assert_eq!(
 			coup.history,
-			vec![History::CounterChallengeCaptainAmbassedor {
-				by: String::from("StaticBot 2"),
-				target: String::from("StaticBot")
-			}]
+			vec![
+				History::CounterChallengeCaptainAmbassedor {
+					by: String::from("StaticBot 2"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot 2"),
+					card: Card::Ambassador
+				}
+			]
 		);
 		coup.history = vec![];
 
@@ -3896,25 +6477,291 @@ mod tests {
 		coup.bots[1].cards = vec![Card::Ambassador, Card::Ambassador];
 
 		let result = coup.resolve_counter_challenge(
-			Counter::Stealing,
+			&Action::Stealing(String::from("StaticBot")),
 			String::from("StaticBot"),
 			String::from("StaticBot 2"),
 		);
 
-		assert_eq!(result, false);
+		assert!(!result);
 		assert_eq!(coup.bots[0].cards, vec![Card::Duke, Card::Ambassador]);
 		assert_eq!(coup.bots[1].cards, vec![Card::Ambassador]);
 		assert_eq!(
 			coup.history,
-			vec![History::CounterChallengeCaptainAmbassedor {
-				by: String::from("StaticBot 2"),
-				target: String::from("StaticBot")
-			}]
+			vec![
+				History::CounterChallengeCaptainAmbassedor {
+					by: String::from("StaticBot 2"),
+					target: String::from("StaticBot")
+				},
+				History::CardLoss {
+					by: String::from("StaticBot 2"),
+					card: Card::Ambassador
+				}
+			]
 		);
 	}
 
 	// TODO: test_display_score
 
+	#[test]
+	fn test_audit() {
+		let mut coup = Coup::new(vec![Box::new(StaticBot), Box::new(StaticBot)]);
+		coup.log = false;
+		coup.play();
+		assert!(coup.audit().is_empty());
+
+		let mut bluffs = 0;
+		let mut exchanges = 0;
+		for seed in 0..20 {
+			let mut coup = Coup::new_with_config(
+				vec![
+					Box::new(RandomBot),
+					Box::new(RandomBot),
+					Box::new(HonestBot),
+				],
+				Config {
+					seed: Some(seed),
+					..Config::default()
+				},
+			);
+			coup.log = false;
+			coup.set_audit(true);
+			coup.play();
+
+			let audit = coup.audit();
+			assert!(matches!(
+				audit[0],
+				AuditEntry {
+					history: 0,
+					event: AuditEvent::Shuffle { .. }
+				}
+			));
+			for entry in audit.iter() {
+				match &entry.event {
+					AuditEvent::Claim { by, hand, .. }
+					| AuditEvent::Disclaim { by, hand, .. } => {
+						assert!(!hand.is_empty());
+						assert!(format!("{:?}", coup.history[entry.history])
+							.contains(&format!("by: {:?}", by)));
+						if by == "HonestBot" {
+							assert_eq!(entry.event.truthful(), Some(true));
+						} else if entry.event.truthful() == Some(false) {
+							bluffs += 1;
+						}
+					},
+					AuditEvent::Exchange {
+						drawn, returned, ..
+					} => {
+						assert_eq!(drawn.len(), returned.len());
+						exchanges += 1;
+					},
+					AuditEvent::Shuffle { .. } => {},
+				}
+			}
+		}
+		assert!(bluffs > 0);
+		assert!(exchanges > 0);
+
+		let claim = AuditEvent::Claim {
+			by: String::from("StaticBot"),
+			cards: vec![Card::Captain, Card::Ambassador],
+			hand: vec![Card::Duke, Card::Ambassador],
+		};
+		assert_eq!(claim.truthful(), Some(true));
+		let disclaim = AuditEvent::Disclaim {
+			by: String::from("StaticBot"),
+			card: Card::Duke,
+			hand: vec![Card::Duke, Card::Ambassador],
+		};
+		assert_eq!(disclaim.truthful(), Some(false));
+	}
+
+	#[test]
+	fn test_bluff_report() {
+		let a = String::from("A");
+		let b = String::from("B");
+		let claim =
+			|history, by: &String, cards: Vec<Card>, hand: Vec<Card>| AuditEntry {
+				history,
+				event: AuditEvent::Claim {
+					by: by.clone(),
+					cards,
+					hand,
+				},
+			};
+		let history = vec![
+			// A bluffs the Duke and isn't challenged
+			History::ActionTax { by: a.clone() },
+			// A bluffs the Duke and B catches it
+			History::ActionTax { by: a.clone() },
+			History::ChallengeDuke {
+				by: b.clone(),
+				target: a.clone(),
+			},
+			History::CardLoss {
+				by: a.clone(),
+				card: Card::Captain,
+			},
+			// B steals with its Captain, A bluffs a counter and B challenges the
+			// counter
+			History::ActionStealing {
+				by: b.clone(),
+				target: a.clone(),
+			},
+			History::CounterStealing {
+				by: a.clone(),
+				target: b.clone(),
+			},
+			History::CounterChallengeCaptainAmbassedor {
+				by: b.clone(),
+				target: a.clone(),
+			},
+			History::CardLoss {
+				by: a.clone(),
+				card: Card::Contessa,
+			},
+			// A claims the Duke truthfully and B challenges it
+			History::ActionTax { by: a.clone() },
+			History::ChallengeDuke {
+				by: b.clone(),
+				target: a.clone(),
+			},
+		];
+		let audit = vec![
+			AuditEntry {
+				history: 0,
+				event: AuditEvent::Shuffle { deck: vec![] },
+			},
+			claim(0, &a, vec![Card::Duke], vec![Card::Captain, Card::Contessa]),
+			claim(1, &a, vec![Card::Duke], vec![Card::Captain, Card::Contessa]),
+			claim(4, &b, vec![Card::Captain], vec![Card::Captain]),
+			claim(5, &a, vec![Card::Captain, Card::Ambassador], vec![Card::Contessa]),
+			claim(8, &a, vec![Card::Duke], vec![Card::Duke]),
+		];
+
+		let mut report = BluffReport::default();
+		report.add_game(&[a.clone(), b.clone()], &history, &audit);
+		report.add_game(&[a.clone(), b.clone()], &[], &[]);
+
+		let stats = report.get("A").unwrap();
+		assert_eq!(stats.games, 2);
+		assert_eq!(stats.bluffed(Card::Duke), 2);
+		assert_eq!(stats.bluffs_challenged(Card::Duke), 1);
+		assert_eq!(stats.bluffed(Card::Captain), 0);
+		assert_eq!(stats.real_counters, 0);
+		assert_eq!(stats.bluffed_counters, 1);
+		assert_eq!(stats.challenges, 0);
+		let stats = report.get("B").unwrap();
+		assert_eq!(stats.total_bluffs(), 0);
+		assert_eq!(stats.challenges, 3);
+		assert_eq!(stats.truthful_challenges, 1);
+		assert!(report.get("C").is_none());
+
+		let mut coup = Coup::new_with_config(
+			vec![
+				Box::new(RandomBot),
+				Box::new(RandomBot),
+				Box::new(HonestBot),
+			],
+			Config {
+				seed: Some(42),
+				..Config::default()
+			},
+		);
+		coup.looping(20);
+		assert!(coup.bluff_report().is_none());
+		assert!(coup.audit().is_empty());
+
+		coup.set_bluff_report(true);
+		coup.looping(20);
+		assert!(coup.audit().is_empty());
+		let report = coup.bluff_report().unwrap();
+		let honest = report.get("HonestBot").unwrap();
+		assert_eq!(honest.games, 20);
+		assert_eq!(honest.total_bluffs(), 0);
+		assert_eq!(honest.bluffed_counters, 0);
+		let random = report.get("RandomBot").unwrap();
+		assert!(random.total_bluffs() > 0);
+		assert!(random.total_bluffs_challenged() <= random.total_bluffs());
+		assert!(format!("{}", report).contains("HonestBot"));
+	}
+
+	#[test]
+	fn test_looping_capture() {
+		struct PenaltyBot;
+		impl BotInterface for PenaltyBot {
+			fn get_name(&self) -> String {
+				String::from("PenaltyBot")
+			}
+			fn on_turn(&self, _context: &Context) -> Action {
+				Action::Coup(String::from("Nobody"))
+			}
+		}
+
+		struct PanicBot;
+		impl BotInterface for PanicBot {
+			fn get_name(&self) -> String {
+				String::from("PanicBot")
+			}
+			fn on_turn(&self, _context: &Context) -> Action {
+				panic!("PanicBot gave up");
+			}
+		}
+
+		let folder = std::env::temp_dir()
+			.join(format!("coup-test-capture-{}", std::process::id()));
+		let captured = |reason: &str| {
+			let mut files = std::fs::read_dir(&folder)
+				.map(|files| {
+					files
+						.map(|file| file.unwrap().path())
+						.filter(|path| path.to_string_lossy().ends_with(reason))
+						.collect::<Vec<_>>()
+				})
+				.unwrap_or_default();
+			files.sort();
+			files
+		};
+
+		let mut coup = Coup::new(vec![Box::new(PenaltyBot), Box::new(StaticBot)]);
+		coup.set_capture(Capture::new(&folder));
+		coup.looping(3);
+		let files = captured("-penalty.replay");
+		assert_eq!(files.len(), 3);
+		let text = std::fs::read_to_string(&files[0]).unwrap();
+		assert!(text.starts_with("# round 1\n# reason Penalty\n# starting hand "));
+		assert!(text.contains("is being penalized because it tried to coup"));
+		assert!(text.contains("\n# audit 0 Shuffle { deck: ["));
+		assert!(Replay::load(&files[0]).unwrap().run().is_ok());
+
+		let mut coup = Coup::new(vec![Box::new(PanicBot), Box::new(StaticBot)]);
+		coup.set_capture(Capture::new(&folder));
+		coup.looping(2);
+		let files = captured("-panic.replay");
+		assert_eq!(files.len(), 2);
+		let text = std::fs::read_to_string(&files[0]).unwrap();
+		assert!(text.contains("# reason Panic(\"PanicBot gave up\")"));
+
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(StaticBot), Box::new(StaticBot)],
+			Config {
+				max_moves: 2,
+				..Config::default()
+			},
+		);
+		coup.set_capture(Capture::new(&folder));
+		coup.looping(2);
+		assert_eq!(captured("-max-moves.replay").len(), 2);
+
+		let mut coup = Coup::new(vec![Box::new(StaticBot), Box::new(StaticBot)]);
+		coup.set_capture(Capture::new(&folder).with_predicate(
+			|result, history| result.winners.len() == 1 && !history.is_empty(),
+		));
+		coup.looping(4);
+		assert_eq!(captured("-predicate.replay").len(), 4);
+
+		std::fs::remove_dir_all(&folder).unwrap();
+	}
+
 	#[test]
 	fn test_format_number_with_separator() {
 		assert_eq!(Coup::format_number_with_separator(0), String::from("0"));
@@ -3936,6 +6783,83 @@ mod tests {
 		// just making sure looping doesn't panic here. Testing it further is hard
 	}
 
+	#[test]
+	fn test_looping_seeds() {
+		use crate::bots::RandomBot;
+
+		let config = Config {
+			seed: Some(5),
+			..Config::default()
+		};
+		let mut looped = Coup::new_with_config(
+			vec![Box::new(RandomBot), Box::new(RandomBot)],
+			config.clone(),
+		);
+		looped.looping(3);
+
+		// Each game of a loop is set up once, just like a game played on its own
+		let mut played = Coup::new_with_config(
+			vec![Box::new(RandomBot), Box::new(RandomBot)],
+			config,
+		);
+		played.log = false;
+		for _ in 0..3 {
+			played.play();
+		}
+		assert_eq!(looped.seed, played.seed);
+		assert_eq!(looped.history, played.history);
+	}
+
+	#[test]
+	fn test_seed() {
+		let play = |seed| {
+			let mut coup = Coup::new_with_config(
+				vec![
+					Box::new(RandomBot),
+					Box::new(RandomBot),
+					Box::new(RandomBot),
+					Box::new(HonestBot),
+				],
+				Config {
+					seed: Some(seed),
+					..Config::default()
+				},
+			);
+			coup.log = false;
+			coup.looping(5);
+			(coup.history.clone(), coup.score.clone())
+		};
+
+		assert_eq!(play(42), play(42));
+		assert_ne!(play(42), play(7));
+	}
+
+	#[test]
+	fn test_looping_ten_players() {
+		use crate::bots::{HonestBot, RandomBot};
+
+		let mut coup = Coup::new_with_config(
+			vec![
+				Box::new(StaticBot),
+				Box::new(HonestBot),
+				Box::new(RandomBot),
+				Box::new(StaticBot),
+				Box::new(HonestBot),
+				Box::new(RandomBot),
+				Box::new(StaticBot),
+				Box::new(HonestBot),
+				Box::new(RandomBot),
+				Box::new(RandomBot),
+			],
+			Config {
+				max_players: 10,
+				..Config::default()
+			},
+		);
+		coup.looping(500);
+		// just making sure the engine doesn't panic with a full table
+	}
+
 	// *******************************| Actions |****************************** //
 	#[test]
 	fn test_action_assassination() {
@@ -4115,6 +7039,21 @@ mod tests {
 		assert_eq!(coup.bots[0].cards, vec![Card::Ambassador, Card::Captain]);
 		assert_eq!(coup.bots[1].cards, vec![Card::Assassin, Card::Captain]);
 		assert_eq!(coup.deck.len(), 2);
+		assert_eq!(
+			coup.get_context(String::from("TestBot")).known_cards,
+			vec![
+				KnownCard {
+					card: Card::Assassin,
+					source: KnownCardSource::Returned,
+					turn: 0,
+				},
+				KnownCard {
+					card: Card::Duke,
+					source: KnownCardSource::Returned,
+					turn: 0,
+				},
+			]
+		);
 	}
 
 	#[test]
@@ -4230,4 +7169,1478 @@ mod tests {
 		assert_eq!(coup.bots[0].coins, 5);
 		assert_eq!(coup.bots[1].coins, 2);
 	}
+
+	#[test]
+	fn test_custom_role_cost_and_exchange() {
+		// The Duke charges a coin for tax and the Ambassador only exchanges one
+		// card
+		let mut roles = RoleSet::classic();
+		roles.roles[0].exchange_cards = 1;
+		roles.roles[4].actions = vec![(Action::Tax, 1)];
+
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(StaticBot), Box::new(StaticBot)],
+			Config {
+				roles: Some(roles),
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.playing_bots = vec![0, 1];
+		coup.bots[0].cards = vec![Card::Ambassador, Card::Duke];
+		coup.deck = vec![Card::Assassin, Card::Contessa];
+
+		coup.action_tax();
+		assert_eq!(coup.bots[0].coins, 4);
+
+		coup.action_swapping();
+		assert_eq!(coup.bots[0].cards, vec![Card::Ambassador, Card::Duke]);
+		assert_eq!(coup.deck.len(), 2);
+
+		// Without the coin for tax the bot is penalized
+		coup.bots[0].coins = 0;
+		coup.action_tax();
+		assert_eq!(coup.bots[0].coins, 0);
+		assert_eq!(coup.bots[0].cards.len(), 1);
+	}
+
+	#[test]
+	#[should_panic(expected = "Roles can only exchange 1 or 2 cards")]
+	fn test_new_with_config_exchange_too_big() {
+		let mut roles = RoleSet::classic();
+		roles.roles[0].exchange_cards = 3;
+		Coup::new_with_config(
+			vec![Box::new(StaticBot), Box::new(StaticBot)],
+			Config {
+				roles: Some(roles),
+				..Config::default()
+			},
+		);
+	}
+
+	#[test]
+	fn test_action_conversion() {
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(StaticBot), Box::new(StaticBot)],
+			Config {
+				reformation: true,
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.playing_bots = vec![0, 1];
+		coup.bots[0].allegiance = Some(Allegiance::Loyalist);
+		coup.bots[1].allegiance = Some(Allegiance::Reformist);
+
+		coup.action_conversion(String::from("StaticBot"));
+
+		assert_eq!(coup.bots[0].coins, 1);
+		assert_eq!(coup.bots[0].allegiance, Some(Allegiance::Reformist));
+		assert_eq!(coup.treasury_reserve, 1);
+
+		coup.bots[0].coins = 2;
+		coup.action_conversion(String::from("StaticBot 2"));
+
+		assert_eq!(coup.bots[0].coins, 0);
+		assert_eq!(coup.bots[1].allegiance, Some(Allegiance::Loyalist));
+		assert_eq!(coup.treasury_reserve, 3);
+	}
+
+	#[test]
+	fn test_action_conversion_insufficient_funds() {
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(StaticBot), Box::new(StaticBot)],
+			Config {
+				reformation: true,
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.playing_bots = vec![0, 1];
+		coup.bots[0].coins = 1;
+		coup.bots[0].cards = vec![Card::Ambassador, Card::Duke];
+		coup.bots[1].allegiance = Some(Allegiance::Reformist);
+
+		coup.action_conversion(String::from("StaticBot 2"));
+
+		assert_eq!(coup.bots[0].coins, 1);
+		assert_eq!(coup.bots[0].cards, vec![Card::Ambassador]);
+		assert_eq!(coup.bots[1].allegiance, Some(Allegiance::Reformist));
+		assert_eq!(coup.treasury_reserve, 0);
+	}
+
+	#[test]
+	fn test_action_conversion_full_reserve() {
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(StaticBot), Box::new(StaticBot)],
+			Config {
+				reformation: true,
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.playing_bots = vec![0, 1];
+		coup.bots[0].coins = 5;
+		coup.treasury_reserve = u8::MAX;
+
+		coup.action_conversion(String::from("StaticBot"));
+
+		assert_eq!(coup.bots[0].coins, 4);
+		assert_eq!(coup.treasury_reserve, u8::MAX);
+
+		coup.bots[0].coins = 250;
+		coup.action_embezzlement();
+
+		assert_eq!(coup.bots[0].coins, u8::MAX);
+		assert_eq!(coup.treasury_reserve, 0);
+
+		// The public state stops at the same amounts
+		let names = vec![String::from("A"), String::from("B")];
+		let mut start = PublicState::new(&names);
+		start.bots[0].coins = 250;
+		start.treasury_reserve = u8::MAX - 1;
+		let history = vec![
+			History::ActionConversion {
+				by: String::from("A"),
+				target: String::from("A"),
+			},
+			History::ActionConversion {
+				by: String::from("A"),
+				target: String::from("A"),
+			},
+			History::ActionEmbezzlement {
+				by: String::from("A"),
+			},
+		];
+		let snapshots =
+			PublicState::replay_from(start, &history, &RoleSet::classic());
+		assert_eq!(snapshots[2].treasury_reserve, u8::MAX);
+		assert_eq!(snapshots[3].bot("A").unwrap().coins, u8::MAX);
+		assert_eq!(snapshots[3].treasury_reserve, 0);
+	}
+
+	#[test]
+	fn test_action_embezzlement() {
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(StaticBot), Box::new(StaticBot)],
+			Config {
+				reformation: true,
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.playing_bots = vec![0, 1];
+		coup.treasury_reserve = 5;
+
+		coup.action_embezzlement();
+
+		assert_eq!(coup.bots[0].coins, 7);
+		assert_eq!(coup.bots[1].coins, 2);
+		assert_eq!(coup.treasury_reserve, 0);
+	}
+
+	#[test]
+	fn test_game_loop_reformation() {
+		struct EmbezzlementBot;
+		impl BotInterface for EmbezzlementBot {
+			fn get_name(&self) -> String {
+				String::from("EmbezzlementBot")
+			}
+			fn on_turn(&self, _context: &Context) -> Action {
+				Action::Embezzlement
+			}
+		}
+		struct EmbezzlementChallengeBot;
+		impl BotInterface for EmbezzlementChallengeBot {
+			fn get_name(&self) -> String {
+				String::from("EmbezzlementChallengeBot")
+			}
+			fn on_challenge_embezzlement(
+				&self,
+				_by: String,
+				_context: &Context,
+			) -> bool {
+				true
+			}
+		}
+		struct AssassinationBot;
+		impl BotInterface for AssassinationBot {
+			fn get_name(&self) -> String {
+				String::from("AssassinationBot")
+			}
+			fn on_turn(&self, _context: &Context) -> Action {
+				Action::Assassination(String::from("StaticBot"))
+			}
+		}
+
+		// Embezzlement challenge successful
+		let mut coup = Coup::new_with_config(
+			vec![
+				Box::new(EmbezzlementBot),
+				Box::new(EmbezzlementChallengeBot),
+			],
+			Config {
+				reformation: true,
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.bots[0].cards = vec![Card::Duke, Card::Captain];
+		coup.playing_bots = vec![0, 1];
+		coup.treasury_reserve = 3;
+		coup.turn = 0;
+		coup.history = vec![];
+
+		coup.game_loop();
+
+		assert_eq!(coup.bots[0].cards, vec![Card::Duke]);
+		assert_eq!(coup.bots[0].coins, 2);
+		assert_eq!(coup.bots[1].cards.len(), 2);
+		assert_eq!(coup.treasury_reserve, 3);
+		assert_eq!(
+			coup.history,
+			vec![
+				History::ActionEmbezzlement {
+					by: String::from("EmbezzlementBot")
+				},
+				History::ChallengeEmbezzlement {
+					by: String::from("EmbezzlementChallengeBot"),
+					target: String::from("EmbezzlementBot")
+				},
+				History::CardLoss {
+					by: String::from("EmbezzlementBot"),
+					card: Card::Captain
+				}
+			]
+		);
+
+		// Embezzlement challenge unsuccessful
+		let mut coup = Coup::new_with_config(
+			vec![
+				Box::new(EmbezzlementBot),
+				Box::new(EmbezzlementChallengeBot),
+			],
+			Config {
+				reformation: true,
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.bots[0].cards = vec![Card::Assassin, Card::Captain];
+		coup.playing_bots = vec![0, 1];
+		coup.treasury_reserve = 3;
+		coup.turn = 0;
+		coup.history = vec![];
+
+		coup.game_loop();
+
+		assert_eq!(coup.bots[0].cards.len(), 2);
+		assert_eq!(coup.bots[0].coins, 5);
+		assert_eq!(coup.bots[1].cards.len(), 1);
+		assert_eq!(coup.treasury_reserve, 0);
+
+		// Targeting your own faction
+		let mut coup = Coup::new_with_config(
+			vec![
+				Box::new(AssassinationBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+			],
+			Config {
+				reformation: true,
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.bots[0].cards = vec![Card::Duke, Card::Assassin];
+		coup.bots[0].coins = 3;
+		coup.bots[0].allegiance = Some(Allegiance::Loyalist);
+		coup.bots[1].allegiance = Some(Allegiance::Loyalist);
+		coup.bots[2].allegiance = Some(Allegiance::Reformist);
+		coup.playing_bots = vec![0, 1, 2];
+		coup.turn = 0;
+		coup.history = vec![];
+
+		coup.game_loop();
+
+		assert_eq!(coup.bots[0].cards, vec![Card::Duke]);
+		assert_eq!(coup.bots[0].coins, 3);
+		assert_eq!(coup.bots[1].cards.len(), 2);
+		assert_eq!(
+			coup.history,
+			vec![History::CardLoss {
+				by: String::from("AssassinationBot"),
+				card: Card::Assassin
+			}]
+		);
+
+		// Reformation actions without the expansion
+		let mut coup =
+			Coup::new(vec![Box::new(EmbezzlementBot), Box::new(StaticBot)]);
+		coup.setup();
+		coup.bots[0].cards = vec![Card::Duke, Card::Assassin];
+		coup.playing_bots = vec![0, 1];
+		coup.turn = 0;
+		coup.history = vec![];
+
+		coup.game_loop();
+
+		assert_eq!(coup.bots[0].cards, vec![Card::Duke]);
+		assert_eq!(
+			coup.history,
+			vec![History::CardLoss {
+				by: String::from("EmbezzlementBot"),
+				card: Card::Assassin
+			}]
+		);
+	}
+
+	#[test]
+	fn test_game_loop_no_targets() {
+		let config = Config {
+			reformation: true,
+			teams: Some(vec![0, 0, 1]),
+			seed: Some(7),
+			..Config::default()
+		};
+
+		// A bot with 10 coins and nobody to coup isn't forced to coup
+		let mut coup = Coup::new_with_config(
+			vec![
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+				Box::new(StaticBot),
+			],
+			config.clone(),
+		);
+		coup.setup();
+		coup.log = false;
+		coup.bots[0].allegiance = Some(Allegiance::Loyalist);
+		coup.bots[1].allegiance = Some(Allegiance::Reformist);
+		coup.bots[2].allegiance = Some(Allegiance::Loyalist);
+		coup.bots[0].coins = 10;
+		coup.playing_bots = vec![0, 1, 2];
+		coup.turn = 0;
+		coup.penalties = 0;
+
+		coup.game_loop();
+
+		assert_eq!(coup.penalties, 0);
+		assert_eq!(coup.bots[0].cards.len(), 2);
+		assert_eq!(coup.bots[0].coins, 11);
+		assert_eq!(
+			coup.history.last(),
+			Some(&History::ActionIncome {
+				by: String::from("StaticBot")
+			})
+		);
+
+		// HonestBot keeps playing without anyone to target
+		let mut coup = Coup::new_with_config(
+			vec![
+				Box::new(HonestBot),
+				Box::new(HonestBot),
+				Box::new(HonestBot),
+			],
+			config,
+		);
+		coup.log = false;
+		for _ in 0..20 {
+			coup.play();
+			assert_eq!(coup.penalties, 0);
+		}
+	}
+
+	#[test]
+	fn test_action_swapping_inquisitor() {
+		struct TestBot;
+		impl BotInterface for TestBot {
+			fn get_name(&self) -> String {
+				String::from("TestBot")
+			}
+			fn on_swapping_card(&self, _new_card: Card, context: &Context) -> Card {
+				context.cards[0]
+			}
+		}
+
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(TestBot), Box::new(StaticBot)],
+			Config {
+				deck: Deck::Inquisitor,
+				..Config::default()
+			},
+		);
+		coup.setup();
+
+		coup.bots[0].cards = vec![Card::Inquisitor, Card::Duke];
+		coup.bots[1].cards = vec![Card::Assassin, Card::Captain];
+		coup.playing_bots = vec![0, 1];
+		coup.deck = vec![Card::Assassin, Card::Contessa];
+
+		coup.action_swapping();
+
+		assert_eq!(coup.bots[0].cards, vec![Card::Duke, Card::Contessa]);
+		assert_eq!(coup.bots[1].cards, vec![Card::Assassin, Card::Captain]);
+		assert_eq!(coup.deck.len(), 2);
+		assert!(coup.deck.contains(&Card::Inquisitor));
+	}
+
+	#[test]
+	fn test_action_swapping_inquisitor_faulty_bot() {
+		struct TestBot;
+		impl BotInterface for TestBot {
+			fn get_name(&self) -> String {
+				String::from("TestBot")
+			}
+			fn on_swapping_card(&self, _new_card: Card, _context: &Context) -> Card {
+				Card::Captain
+			}
+		}
+
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(TestBot), Box::new(StaticBot)],
+			Config {
+				deck: Deck::Inquisitor,
+				..Config::default()
+			},
+		);
+		coup.setup();
+
+		coup.bots[0].cards = vec![Card::Inquisitor, Card::Duke];
+		coup.bots[1].cards = vec![Card::Assassin, Card::Captain];
+		coup.playing_bots = vec![0, 1];
+		coup.deck = vec![Card::Assassin, Card::Contessa];
+
+		coup.action_swapping();
+
+		assert_eq!(coup.bots[0].cards, vec![Card::Inquisitor]);
+		assert_eq!(coup.bots[1].cards, vec![Card::Assassin, Card::Captain]);
+	}
+
+	#[test]
+	fn test_action_examine() {
+		struct ExamineBot;
+		impl BotInterface for ExamineBot {
+			fn get_name(&self) -> String {
+				String::from("ExamineBot")
+			}
+			fn on_examined_card(
+				&self,
+				card: Card,
+				_target: String,
+				_context: &Context,
+			) -> bool {
+				card == Card::Contessa
+			}
+		}
+		struct ShowBot;
+		impl BotInterface for ShowBot {
+			fn get_name(&self) -> String {
+				String::from("ShowBot")
+			}
+			fn on_examine(&self, _by: String, context: &Context) -> Card {
+				context.cards[1]
+			}
+		}
+
+		// No swap
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(ExamineBot), Box::new(ShowBot)],
+			Config {
+				deck: Deck::Inquisitor,
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.bots[1].cards = vec![Card::Contessa, Card::Duke];
+		coup.playing_bots = vec![0, 1];
+		coup.deck = vec![Card::Assassin];
+
+		coup.action_examine(String::from("ShowBot"));
+
+		assert_eq!(coup.bots[1].cards, vec![Card::Contessa, Card::Duke]);
+		assert_eq!(coup.deck, vec![Card::Assassin]);
+		assert_eq!(coup.history, vec![]);
+
+		// Forced swap
+		coup.bots[1].cards = vec![Card::Duke, Card::Contessa];
+
+		coup.action_examine(String::from("ShowBot"));
+
+		assert_eq!(coup.bots[1].cards[0], Card::Duke);
+		assert_eq!(coup.bots[1].cards.len(), 2);
+		assert_eq!(coup.deck.len(), 1);
+		assert_eq!(
+			coup.history,
+			vec![History::ActionExamineSwap {
+				by: String::from("ExamineBot"),
+				target: String::from("ShowBot"),
+			}]
+		);
+
+		// Both bots remember the examined cards
+		assert_eq!(
+			coup.get_context(String::from("ExamineBot")).known_cards,
+			vec![
+				KnownCard {
+					card: Card::Duke,
+					source: KnownCardSource::Examined {
+						owner: String::from("ShowBot"),
+					},
+					turn: 0,
+				},
+				KnownCard {
+					card: Card::Contessa,
+					source: KnownCardSource::Examined {
+						owner: String::from("ShowBot"),
+					},
+					turn: 0,
+				},
+			]
+		);
+		assert_eq!(
+			coup.get_context(String::from("ShowBot")).known_cards,
+			vec![KnownCard {
+				card: Card::Contessa,
+				source: KnownCardSource::Shuffled,
+				turn: 0,
+			}]
+		);
+	}
+
+	#[test]
+	fn test_action_examine_faulty_bot() {
+		struct TestBot;
+		impl BotInterface for TestBot {
+			fn get_name(&self) -> String {
+				String::from("TestBot")
+			}
+			fn on_examine(&self, _by: String, _context: &Context) -> Card {
+				Card::Captain
+			}
+		}
+
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(StaticBot), Box::new(TestBot)],
+			Config {
+				deck: Deck::Inquisitor,
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.bots[1].cards = vec![Card::Contessa, Card::Duke];
+		coup.playing_bots = vec![0, 1];
+
+		coup.action_examine(String::from("TestBot"));
+
+		assert_eq!(coup.bots[1].cards, vec![Card::Contessa]);
+		assert_eq!(
+			coup.history,
+			vec![History::CardLoss {
+				by: String::from("TestBot"),
+				card: Card::Duke
+			}]
+		);
+	}
+
+	#[test]
+	fn test_game_loop_inquisitor() {
+		struct ExamineBot;
+		impl BotInterface for ExamineBot {
+			fn get_name(&self) -> String {
+				String::from("ExamineBot")
+			}
+			fn on_turn(&self, _context: &Context) -> Action {
+				Action::Examine(String::from("ActionChallengeBot"))
+			}
+		}
+		struct ActionChallengeBot;
+		impl BotInterface for ActionChallengeBot {
+			fn get_name(&self) -> String {
+				String::from("ActionChallengeBot")
+			}
+			fn on_challenge_action_round(
+				&self,
+				_action: &Action,
+				_by: String,
+				_context: &Context,
+			) -> bool {
+				true
+			}
+		}
+
+		// Challenge unsuccessful
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(ExamineBot), Box::new(ActionChallengeBot)],
+			Config {
+				deck: Deck::Inquisitor,
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.bots[0].cards = vec![Card::Inquisitor, Card::Duke];
+		coup.bots[1].cards = vec![Card::Captain, Card::Duke];
+		coup.playing_bots = vec![0, 1];
+		coup.turn = 0;
+		coup.history = vec![];
+
+		coup.game_loop();
+
+		assert_eq!(coup.bots[0].cards.len(), 2);
+		assert_eq!(coup.bots[1].cards, vec![Card::Captain]);
+		assert_eq!(
+			coup.history,
+			vec![
+				History::ActionExamine {
+					by: String::from("ExamineBot"),
+					target: String::from("ActionChallengeBot")
+				},
+				History::ChallengeInquisitor {
+					by: String::from("ActionChallengeBot"),
+					target: String::from("ExamineBot")
+				},
+				History::CardLoss {
+					by: String::from("ActionChallengeBot"),
+					card: Card::Duke
+				}
+			]
+		);
+
+		// Examine without the Inquisitor in the deck
+		let mut coup =
+			Coup::new(vec![Box::new(ExamineBot), Box::new(ActionChallengeBot)]);
+		coup.setup();
+		coup.bots[0].cards = vec![Card::Ambassador, Card::Duke];
+		coup.playing_bots = vec![0, 1];
+		coup.turn = 0;
+		coup.history = vec![];
+
+		coup.game_loop();
+
+		assert_eq!(coup.bots[0].cards, vec![Card::Ambassador]);
+		assert_eq!(
+			coup.history,
+			vec![History::CardLoss {
+				by: String::from("ExamineBot"),
+				card: Card::Duke
+			}]
+		);
+	}
+
+	#[test]
+	fn test_role_set() {
+		let roles = RoleSet::classic();
+		assert_eq!(
+			roles.cards(),
+			vec![
+				Card::Ambassador,
+				Card::Assassin,
+				Card::Captain,
+				Card::Contessa,
+				Card::Duke
+			]
+		);
+		assert_eq!(roles.deck(6).len(), 15);
+		assert_eq!(
+			roles.claimed_card(&Action::Stealing(String::from("Bob"))),
+			Some(Card::Captain)
+		);
+		assert_eq!(roles.claimed_card(&Action::Swapping), Some(Card::Ambassador));
+		assert_eq!(roles.claimed_card(&Action::Examine(String::from("Bob"))), None);
+		assert_eq!(roles.claimed_card(&Action::Income), None);
+		assert_eq!(roles.disclaimed_card(&Action::Embezzlement), Some(Card::Duke));
+		assert!(!roles.allows(&Action::Examine(String::from("Bob"))));
+		assert_eq!(
+			roles.blockers(&Action::Stealing(String::from("Bob"))),
+			vec![Card::Ambassador, Card::Captain]
+		);
+		assert_eq!(roles.blockers(&Action::ForeignAid), vec![Card::Duke]);
+		assert_eq!(roles.blockers(&Action::Tax), vec![]);
+		assert_eq!(roles.cost(&Action::Assassination(String::from("Bob"))), 3);
+		assert_eq!(roles.cost(&Action::Tax), 0);
+		assert_eq!(roles.exchange_cards(), 2);
+
+		let roles = RoleSet::inquisitor();
+		assert_eq!(roles.exchange_cards(), 1);
+		assert_eq!(roles.claimed_card(&Action::Swapping), Some(Card::Inquisitor));
+		assert_eq!(
+			roles.claimed_card(&Action::Examine(String::from("Bob"))),
+			Some(Card::Inquisitor)
+		);
+		assert_eq!(
+			roles.blockers(&Action::Stealing(String::from("Bob"))),
+			vec![Card::Inquisitor, Card::Captain]
+		);
+	}
+
+	#[test]
+	fn test_game_loop_custom_roles() {
+		struct TaxBot;
+		impl BotInterface for TaxBot {
+			fn get_name(&self) -> String {
+				String::from("TaxBot")
+			}
+			fn on_turn(&self, _context: &Context) -> Action {
+				Action::Tax
+			}
+		}
+		struct ForeignAidBot;
+		impl BotInterface for ForeignAidBot {
+			fn get_name(&self) -> String {
+				String::from("ForeignAidBot")
+			}
+			fn on_turn(&self, _context: &Context) -> Action {
+				Action::ForeignAid
+			}
+		}
+		struct ForeignAidChallengeBot;
+		impl BotInterface for ForeignAidChallengeBot {
+			fn get_name(&self) -> String {
+				String::from("ForeignAidChallengeBot")
+			}
+			fn on_turn(&self, _context: &Context) -> Action {
+				Action::ForeignAid
+			}
+			fn on_challenge_counter_round(
+				&self,
+				_action: &Action,
+				_by: String,
+				_context: &Context,
+			) -> bool {
+				true
+			}
+		}
+		struct ChallengeBot;
+		impl BotInterface for ChallengeBot {
+			fn get_name(&self) -> String {
+				String::from("ChallengeBot")
+			}
+			fn on_challenge_action_round(
+				&self,
+				_action: &Action,
+				_by: String,
+				_context: &Context,
+			) -> bool {
+				true
+			}
+			fn on_counter(
+				&self,
+				_action: &Action,
+				_by: String,
+				_context: &Context,
+			) -> bool {
+				true
+			}
+			fn on_challenge_counter_round(
+				&self,
+				_action: &Action,
+				_by: String,
+				_context: &Context,
+			) -> bool {
+				true
+			}
+		}
+
+		// The Contessa takes tax and blocks foreign aid instead of the Duke
+		let mut roles = RoleSet::classic();
+		roles.roles[3].actions = vec![(Action::Tax, 0)];
+		roles.roles[3].blocks.push(Counter::ForeignAid);
+		roles.roles[4].actions = vec![];
+		roles.roles[4].blocks = vec![];
+
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(TaxBot), Box::new(ChallengeBot)],
+			Config {
+				roles: Some(roles.clone()),
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.bots[0].cards = vec![Card::Contessa, Card::Duke];
+		coup.bots[1].cards = vec![Card::Captain, Card::Captain];
+		coup.playing_bots = vec![0, 1];
+		coup.turn = 0;
+		coup.history = vec![];
+
+		coup.game_loop();
+
+		assert_eq!(coup.bots[0].coins, 5);
+		assert_eq!(coup.bots[1].cards, vec![Card::Captain]);
+		assert_eq!(
+			coup.history,
+			vec![
+				History::ActionTax {
+					by: String::from("TaxBot")
+				},
+				History::ChallengeContessa {
+					by: String::from("ChallengeBot"),
+					target: String::from("TaxBot")
+				},
+				History::CardLoss {
+					by: String::from("ChallengeBot"),
+					card: Card::Captain
+				}
+			]
+		);
+
+		// The Contessa blocks foreign aid
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(ForeignAidBot), Box::new(ChallengeBot)],
+			Config {
+				roles: Some(roles.clone()),
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.bots[0].cards = vec![Card::Duke, Card::Duke];
+		coup.bots[1].cards = vec![Card::Contessa, Card::Captain];
+		coup.playing_bots = vec![0, 1];
+		coup.turn = 0;
+		coup.history = vec![];
+
+		coup.game_loop();
+
+		assert_eq!(coup.bots[0].coins, 2);
+		assert_eq!(
+			coup.history,
+			vec![
+				History::ActionForeignAid {
+					by: String::from("ForeignAidBot"),
+				},
+				History::CounterForeignAid {
+					by: String::from("ChallengeBot"),
+					target: String::from("ForeignAidBot"),
+				}
+			]
+		);
+
+		// Challenging that counter is a challenge of the Contessa
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(ForeignAidChallengeBot), Box::new(ChallengeBot)],
+			Config {
+				roles: Some(roles.clone()),
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.bots[0].cards = vec![Card::Duke, Card::Duke];
+		coup.bots[1].cards = vec![Card::Contessa, Card::Captain];
+		coup.playing_bots = vec![0, 1];
+		coup.turn = 0;
+		coup.history = vec![];
+
+		coup.game_loop();
+
+		assert_eq!(coup.bots[0].coins, 2);
+		assert_eq!(coup.bots[0].cards, vec![Card::Duke]);
+		assert_eq!(
+			coup.history[2..],
+			[
+				History::CounterChallengeContessa {
+					by: String::from("ForeignAidChallengeBot"),
+					target: String::from("ChallengeBot"),
+				},
+				History::CardLoss {
+					by: String::from("ForeignAidChallengeBot"),
+					card: Card::Duke
+				}
+			]
+		);
+
+		// Actions no role allows are penalized
+		roles.roles[3].actions = vec![];
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(TaxBot), Box::new(ChallengeBot)],
+			Config {
+				roles: Some(roles),
+				..Config::default()
+			},
+		);
+		coup.setup();
+		coup.bots[0].cards = vec![Card::Contessa, Card::Duke];
+		coup.playing_bots = vec![0, 1];
+		coup.turn = 0;
+		coup.history = vec![];
+
+		coup.game_loop();
+
+		assert_eq!(coup.bots[0].coins, 2);
+		assert_eq!(coup.bots[0].cards, vec![Card::Contessa]);
+		assert_eq!(
+			coup.history,
+			vec![History::CardLoss {
+				by: String::from("TaxBot"),
+				card: Card::Duke
+			}]
+		);
+	}
+
+	#[test]
+	fn test_belief() {
+		let mut coup = Coup::new(vec![
+			Box::new(StaticBot),
+			Box::new(StaticBot),
+			Box::new(StaticBot),
+		]);
+		coup.setup();
+		coup.bots[0].cards = vec![Card::Duke, Card::Duke];
+		coup.bots[1].cards = vec![Card::Captain, Card::Assassin];
+		coup.bots[2].cards = vec![Card::Contessa];
+		coup.discard_pile = vec![Card::Duke];
+		coup.playing_bots = vec![0, 1, 2];
+		coup.seated_bots = vec![0, 1, 2];
+
+		let mut belief = Belief::new(&coup.get_context(String::from("StaticBot")));
+
+		assert_eq!(belief.unseen().len(), 12);
+		assert_eq!(belief.unseen_count(Card::Duke), 0);
+		assert_eq!(belief.unseen_count(Card::Captain), 3);
+		assert_eq!(belief.probability("StaticBot 2", Card::Duke), 0.0);
+		// 1 - 9/12 * 8/11
+		assert!(
+			(belief.probability("StaticBot 2", Card::Captain) - 5.0 / 11.0).abs()
+				< 1e-9
+		);
+		// 1 - 9/12
+		assert!(
+			(belief.probability("StaticBot 3", Card::Captain) - 0.25).abs() < 1e-9
+		);
+		assert_eq!(belief.probability("Unknown", Card::Captain), 0.0);
+		assert_eq!(belief.probabilities("StaticBot 3").len(), 5);
+
+		// A claim makes the card more likely
+		coup.history.push(History::ActionStealing {
+			by: String::from("StaticBot 2"),
+			target: String::from("StaticBot 3"),
+		});
+		belief.update(&coup.get_context(String::from("StaticBot")));
+		assert!(
+			(belief.probability("StaticBot 2", Card::Captain) - 8.0 / 11.0).abs()
+				< 1e-9
+		);
+
+		// A counter of stealing claims one of two cards
+		coup.history.push(History::CounterStealing {
+			by: String::from("StaticBot 3"),
+			target: String::from("StaticBot 2"),
+		});
+		belief.update(&coup.get_context(String::from("StaticBot")));
+		assert!(
+			(belief.probability("StaticBot 3", Card::Captain) - 0.4375).abs() < 1e-9
+		);
+
+		// A challenge settles the claim
+		coup.history.push(History::ChallengeCaptain {
+			by: String::from("StaticBot 3"),
+			target: String::from("StaticBot 2"),
+		});
+		belief.update(&coup.get_context(String::from("StaticBot")));
+		assert!(
+			(belief.probability("StaticBot 2", Card::Captain) - 5.0 / 11.0).abs()
+				< 1e-9
+		);
+
+		// Embezzling is claiming not to have the Duke
+		coup.discard_pile = vec![];
+		coup.history.push(History::ActionEmbezzlement {
+			by: String::from("StaticBot 3"),
+		});
+		belief.update(&coup.get_context(String::from("StaticBot")));
+		assert_eq!(belief.unseen_count(Card::Duke), 1);
+		// (1 - 12/13) / 2
+		assert!(
+			(belief.probability("StaticBot 3", Card::Duke) - 1.0 / 26.0).abs() < 1e-9
+		);
+
+		// A new game starts over
+		coup.history = vec![];
+		belief.update(&coup.get_context(String::from("StaticBot")));
+		assert!(
+			(belief.probability("StaticBot 3", Card::Captain) - 3.0 / 13.0).abs()
+				< 1e-9
+		);
+
+		// Cards we set aside are out of the game
+		coup.bots[0].known_cards = vec![KnownCard {
+			card: Card::Captain,
+			source: KnownCardSource::SetAside,
+			turn: 0,
+		}];
+		belief.update(&coup.get_context(String::from("StaticBot")));
+		assert_eq!(belief.unseen().len(), 12);
+		assert_eq!(belief.unseen_count(Card::Captain), 2);
+
+		// A card we examined is in the hand of its owner and nowhere else
+		coup.history.push(History::ActionExamine {
+			by: String::from("StaticBot"),
+			target: String::from("StaticBot 2"),
+		});
+		coup.bots[0].known_cards.push(KnownCard {
+			card: Card::Assassin,
+			source: KnownCardSource::Examined {
+				owner: String::from("StaticBot 2"),
+			},
+			turn: 1,
+		});
+		belief.update(&coup.get_context(String::from("StaticBot")));
+		assert_eq!(belief.probability("StaticBot 2", Card::Assassin), 1.0);
+		// 1 - 9/11
+		assert!(
+			(belief.probability("StaticBot 3", Card::Assassin) - 2.0 / 11.0).abs()
+				< 1e-9
+		);
+		// 1 - 9/11 for the card we didn't see
+		assert!(
+			(belief.probability("StaticBot 2", Card::Captain) - 2.0 / 11.0).abs()
+				< 1e-9
+		);
+
+		// Until the owner exchanges its hand
+		coup.history.push(History::ActionSwapping {
+			by: String::from("StaticBot 2"),
+		});
+		belief.update(&coup.get_context(String::from("StaticBot")));
+		// 1 - 9/12 * 8/11
+		assert!(
+			(belief.probability("StaticBot 2", Card::Assassin) - 5.0 / 11.0).abs()
+				< 1e-9
+		);
+	}
+
+	#[test]
+	fn test_profiles() {
+		let mut coup = Coup::new(vec![
+			Box::new(StaticBot),
+			Box::new(StaticBot),
+			Box::new(StaticBot),
+		]);
+		coup.setup();
+		coup.seated_bots = vec![0, 1, 2];
+		coup.history = vec![
+			History::ActionTax {
+				by: String::from("StaticBot 2"),
+			},
+			History::ChallengeDuke {
+				by: String::from("StaticBot 3"),
+				target: String::from("StaticBot 2"),
+			},
+			History::CardLoss {
+				by: String::from("StaticBot 2"),
+				card: Card::Captain,
+			},
+			History::ActionStealing {
+				by: String::from("StaticBot 3"),
+				target: String::from("StaticBot 2"),
+			},
+			History::CounterStealing {
+				by: String::from("StaticBot 2"),
+				target: String::from("StaticBot 3"),
+			},
+			History::CounterChallengeCaptainAmbassedor {
+				by: String::from("StaticBot 3"),
+				target: String::from("StaticBot 2"),
+			},
+			History::CardLoss {
+				by: String::from("StaticBot 3"),
+				card: Card::Duke,
+			},
+			History::ActionTax {
+				by: String::from("StaticBot 2"),
+			},
+			History::ActionTax {
+				by: String::from("StaticBot"),
+			},
+		];
+
+		let profiles =
+			profile::profiles(&coup.get_context(String::from("StaticBot")));
+
+		assert_eq!(profiles.len(), 2);
+		assert_eq!(profiles[0].name, String::from("StaticBot 2"));
+		assert_eq!(profiles[0].claimed(Card::Duke), 2);
+		assert_eq!(profiles[0].claimed(Card::Captain), 0);
+		assert_eq!(profiles[0].countered(Counter::Stealing), 1);
+		assert_eq!(profiles[0].challenged, 2);
+		assert_eq!(profiles[0].caught_bluffing, 1);
+		assert_eq!(profiles[0].survived_challenges, 1);
+		assert_eq!(profiles[0].challenges, 0);
+		assert_eq!(profiles[1].name, String::from("StaticBot 3"));
+		assert_eq!(profiles[1].claimed(Card::Captain), 1);
+		assert_eq!(profiles[1].challenged, 0);
+		assert_eq!(profiles[1].challenges, 2);
+		assert_eq!(profiles[1].successful_challenges, 1);
+	}
+
+	#[test]
+	fn test_public_state_replay() {
+		let history = vec![
+			History::ActionTax {
+				by: String::from("A"),
+			},
+			History::ChallengeDuke {
+				by: String::from("B"),
+				target: String::from("A"),
+			},
+			History::CardLoss {
+				by: String::from("A"),
+				card: Card::Captain,
+			},
+			History::ActionStealing {
+				by: String::from("B"),
+				target: String::from("A"),
+			},
+			History::CounterStealing {
+				by: String::from("A"),
+				target: String::from("B"),
+			},
+			History::CounterChallengeCaptainAmbassedor {
+				by: String::from("B"),
+				target: String::from("A"),
+			},
+			History::CardLoss {
+				by: String::from("A"),
+				card: Card::Duke,
+			},
+			History::ActionForeignAid {
+				by: String::from("B"),
+			},
+		];
+
+		let snapshots = PublicState::replay(&history);
+
+		assert_eq!(snapshots.len(), 4);
+		assert_eq!(
+			snapshots[0],
+			PublicState::new(&[String::from("A"), String::from("B")])
+		);
+		// The Duke was a bluff
+		assert_eq!(snapshots[1].bot("A").unwrap().coins, 2);
+		assert_eq!(snapshots[1].bot("A").unwrap().cards, 1);
+		assert_eq!(snapshots[1].events, 3);
+		// The counter was a bluff so the steal goes through
+		assert_eq!(snapshots[2].bot("A").unwrap().coins, 0);
+		assert_eq!(snapshots[2].bot("A").unwrap().cards, 0);
+		assert_eq!(
+			snapshots[2].bot("A").unwrap().discards,
+			vec![Card::Captain, Card::Duke]
+		);
+		assert_eq!(snapshots[2].bot("B").unwrap().coins, 4);
+		assert_eq!(snapshots[3].bot("B").unwrap().coins, 6);
+		assert_eq!(snapshots[3].moves, 3);
+	}
+
+	// Replays the history of the last game and checks it matches the engine
+	fn assert_replay_matches(coup: &Coup) {
+		let names = coup
+			.seated_bots
+			.iter()
+			.map(|bot_index| coup.bots[*bot_index].name.clone())
+			.collect::<Vec<String>>();
+		let mut start = PublicState::new(&names);
+		for (seat, bot) in start.bots.iter_mut().enumerate() {
+			let engine_bot = &coup.bots[coup.seated_bots[seat]];
+			bot.coins = engine_bot.handicap.unwrap_or_default().coins;
+			bot.cards = coup.starting_hands[seat].1.len();
+		}
+		if coup.config.two_player {
+			start.bots[0].coins = 1;
+		}
+
+		let snapshots = PublicState::replay_from(start, &coup.history, &coup.roles);
+		let last = snapshots.last().unwrap();
+
+		let actions = coup
+			.history
+			.iter()
+			.filter(|event| {
+				matches!(
+					event,
+					History::ActionAssassination { .. }
+						| History::ActionCoup { .. }
+						| History::ActionForeignAid { .. }
+						| History::ActionSwapping { .. }
+						| History::ActionIncome { .. }
+						| History::ActionStealing { .. }
+						| History::ActionTax { .. }
+						| History::ActionConversion { .. }
+						| History::ActionEmbezzlement { .. }
+						| History::ActionExamine { .. }
+				)
+			})
+			.count();
+		assert_eq!(snapshots.len(), actions + 1);
+		assert_eq!(last.events, coup.history.len());
+		assert_eq!(last.treasury_reserve, coup.treasury_reserve);
+		for bot in last.bots.iter() {
+			let engine_bot = coup.get_bot_by_name(bot.name.clone());
+			assert_eq!(bot.coins, engine_bot.coins, "coins of {}", bot.name);
+			assert_eq!(bot.cards, engine_bot.cards.len(), "cards of {}", bot.name);
+		}
+
+		let mut discards = last
+			.bots
+			.iter()
+			.flat_map(|bot| bot.discards.clone())
+			.collect::<Vec<Card>>();
+		let mut discard_pile = coup.discard_pile.clone();
+		discards.sort_by_key(|card| format!("{:?}", card));
+		discard_pile.sort_by_key(|card| format!("{:?}", card));
+		assert_eq!(discards, discard_pile);
+	}
+
+	#[test]
+	fn test_public_state_replay_matches_engine() {
+		// Roles that charge for every action they allow
+		let mut classic_costs = RoleSet::classic();
+		classic_costs.roles[0].actions = vec![(Action::Swapping, 1)];
+		classic_costs.roles[2].actions = vec![(Action::Stealing(String::new()), 1)];
+		classic_costs.roles[4].actions = vec![(Action::Tax, 2)];
+		let mut inquisitor_costs = RoleSet::inquisitor();
+		inquisitor_costs.roles[0].actions =
+			vec![(Action::Swapping, 1), (Action::Examine(String::new()), 2)];
+		inquisitor_costs.roles[4].actions = vec![(Action::Tax, 1)];
+
+		let configs = vec![
+			Config::default(),
+			Config {
+				reformation: true,
+				..Config::default()
+			},
+			Config {
+				deck: Deck::Inquisitor,
+				..Config::default()
+			},
+			Config {
+				handicaps: Some(vec![
+					Handicap { coins: 0, cards: 1 },
+					Handicap::default(),
+					Handicap { coins: 5, cards: 2 },
+					Handicap::default(),
+				]),
+				..Config::default()
+			},
+			Config {
+				roles: Some(classic_costs),
+				..Config::default()
+			},
+			Config {
+				reformation: true,
+				roles: Some(inquisitor_costs),
+				..Config::default()
+			},
+		];
+
+		for config in configs {
+			let mut coup = Coup::new_with_config(
+				vec![
+					Box::new(RandomBot),
+					Box::new(HonestBot),
+					Box::new(RandomBot),
+					Box::new(StaticBot),
+				],
+				config,
+			);
+			coup.log = false;
+			for _ in 0..200 {
+				coup.play();
+				assert_replay_matches(&coup);
+			}
+		}
+
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(RandomBot), Box::new(HonestBot)],
+			Config {
+				two_player: true,
+				..Config::default()
+			},
+		);
+		coup.log = false;
+		for _ in 0..200 {
+			coup.play();
+			assert_replay_matches(&coup);
+		}
+	}
+
+	#[test]
+	fn test_replay() {
+		let mut roles = RoleSet::classic();
+		roles.roles[3].actions = vec![(Action::Tax, 0)];
+		roles.roles[3].disclaimed_actions = vec![Action::Embezzlement];
+
+		let configs = vec![
+			Config::default(),
+			Config {
+				reformation: true,
+				deck: Deck::Inquisitor,
+				tie_breakers: vec![TieBreaker::MostCoins, TieBreaker::Shared],
+				..Config::default()
+			},
+			Config {
+				draft: Some(4),
+				teams: Some(vec![0, 1, 0, 1]),
+				friendly_fire: true,
+				stalemate_repetitions: Some(3),
+				stalemate_rounds: Some(20),
+				..Config::default()
+			},
+			Config {
+				handicaps: Some(vec![
+					Handicap { coins: 0, cards: 1 },
+					Handicap::default(),
+					Handicap { coins: 5, cards: 2 },
+					Handicap::default(),
+				]),
+				roles: Some(roles),
+				seed: Some(3),
+				..Config::default()
+			},
+		];
+
+		for config in configs {
+			let mut coup = Coup::new_with_config(
+				vec![
+					Box::new(RandomBot),
+					Box::new(HonestBot),
+					Box::new(RandomBot),
+					Box::new(StaticBot),
+				],
+				config,
+			);
+			coup.log = false;
+			coup.set_replay(true);
+			for _ in 0..20 {
+				let result = coup.play();
+				let replay = coup.replay();
+				assert_eq!(replay.to_string().parse::<Replay>().unwrap(), replay);
+				assert_eq!(replay.run().unwrap(), result);
+			}
+		}
+
+		let mut coup = Coup::new_with_config(
+			vec![Box::new(RandomBot), Box::new(RandomBot)],
+			Config {
+				two_player: true,
+				..Config::default()
+			},
+		);
+		coup.log = false;
+		coup.play();
+		assert!(coup.replay().decisions.is_empty());
+		coup.set_replay(true);
+		let result = coup.play();
+		let mut replay = coup.replay();
+		assert!(!replay.decisions.is_empty());
+		assert_eq!(replay.run().unwrap(), result);
+
+		// A different decision makes the game go somewhere else
+		let (name, decision) = replay
+			.decisions
+			.iter_mut()
+			.find(|(_, decision)| matches!(decision, Decision::Turn(_)))
+			.unwrap();
+		*decision = Decision::Turn(Action::Coup(name.clone()));
+		assert!(matches!(replay.run(), Err(ReplayError::Mismatch(_))));
+
+		replay.history.pop();
+		let text = replay.to_string().replacen("coup-replay 1", "coup-replay 9", 1);
+		assert!(matches!(text.parse::<Replay>(), Err(ReplayError::Version(9))));
+		let text = replay.to_string().replacen("history", "history Unknown", 1);
+		assert!(matches!(text.parse::<Replay>(), Err(ReplayError::Parse { .. })));
+
+		// A config the engine can't play is an error instead of a panic
+		for (line, malformed) in [
+			("draft none", "draft 9"),
+			("max_players 6", "max_players 11"),
+			("teams none", "teams 0"),
+			("handicaps none", "handicaps 1:2"),
+		] {
+			let text = replay.to_string().replacen(line, malformed, 1);
+			assert_ne!(text, replay.to_string());
+			let malformed = text.parse::<Replay>().unwrap();
+			assert!(matches!(malformed.run(), Err(ReplayError::Config(_))));
+		}
+	}
+
+	#[test]
+	fn test_viewer() {
+		let mut coup = Coup::new_with_config(
+			vec![
+				Box::new(RandomBot),
+				Box::new(RandomBot),
+				Box::new(HonestBot),
+				Box::new(RandomBot),
+			],
+			Config {
+				seed: Some(11),
+				..Config::default()
+			},
+		);
+		coup.log = false;
+		coup.set_replay(true);
+
+		for _ in 0..20 {
+			let result = coup.play();
+			let mut viewer = Viewer::new(&coup.replay()).unwrap();
+			assert_eq!(viewer.len(), coup.history.len());
+			assert_eq!(viewer.position(), 0);
+			assert_eq!(viewer.event(), None);
+			assert!(!viewer.rewind());
+			assert!(viewer.frame().bots.iter().all(|bot| bot.cards.len() == 2));
+
+			let mut eliminated = Vec::new();
+			while viewer.next_elimination() {
+				let Some(History::CardLoss { by, .. }) = viewer.event().cloned() else {
+					panic!("An elimination has to be a card loss");
+				};
+				assert!(viewer
+					.frame()
+					.bots
+					.iter()
+					.any(|bot| bot.name == by && bot.cards.is_empty()));
+				eliminated.push(by);
+			}
+			assert_eq!(
+				eliminated,
+				result
+					.eliminations
+					.iter()
+					.map(|elimination| elimination.name.clone())
+					.collect::<Vec<String>>()
+			);
+			assert_eq!(viewer.position(), viewer.len());
+
+			let last_frame = viewer.frame();
+			for bot in coup.seated_bots.iter() {
+				let bot = &coup.bots[*bot];
+				let frame_bot =
+					last_frame.bots.iter().find(|frame_bot| frame_bot.name == bot.name);
+				assert_eq!(frame_bot.unwrap().cards, bot.cards);
+				assert_eq!(frame_bot.unwrap().coins, bot.coins);
+			}
+			assert_eq!(last_frame.deck, coup.deck);
+
+			let mut challenges = 0;
+			while viewer.previous_challenge() {
+				challenges += 1;
+			}
+			assert_eq!(
+				challenges,
+				coup
+					.history
+					.iter()
+					.filter(|event| format!("{:?}", event).contains("Challenge"))
+					.count()
+			);
+
+			viewer.seek(0);
+			let mut steps = 0;
+			while viewer.step() {
+				steps += 1;
+			}
+			assert_eq!(steps, coup.history.len());
+			assert!(!viewer.render().contains("Deck"));
+			viewer.god_view = true;
+			assert!(viewer.render().contains("Deck"));
+		}
+	}
 }
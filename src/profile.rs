@@ -0,0 +1,215 @@
+//! The [ClaimProfile] of each opponent built from the [History] of a game.
+//!
+//! ```rust
+//! use coup::{
+//!     bot::{BotInterface, Context},
+//!     profile::profiles,
+//!     Action,
+//! };
+//!
+//! pub struct SuspiciousBot;
+//!
+//! impl BotInterface for SuspiciousBot {
+//!     fn get_name(&self) -> String {
+//!         String::from("SuspiciousBot")
+//!     }
+//!
+//!     // Challenge bots that were caught bluffing before
+//!     fn on_challenge_action_round(
+//!         &self,
+//!         _action: &Action,
+//!         by: String,
+//!         context: &Context,
+//!     ) -> bool {
+//!         profiles(context)
+//!             .iter()
+//!             .any(|profile| profile.name == by && profile.caught_bluffing > 0)
+//!     }
+//! }
+//! ```
+
+use crate::{bot::Context, Action, Card, Counter, History};
+
+/// What a bot did in public so far in a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimProfile {
+	/// The name of the bot
+	pub name: String,
+	/// How often this bot claimed each card of the game with an action
+	pub claims: Vec<(Card, usize)>,
+	/// How often this bot played each counter
+	pub counters: Vec<(Counter, usize)>,
+	/// How often other bots challenged an action or counter of this bot
+	pub challenged: usize,
+	/// How often a challenge against this bot caught it bluffing
+	pub caught_bluffing: usize,
+	/// How often a challenge against this bot failed
+	pub survived_challenges: usize,
+	/// How often this bot challenged an action or counter of another bot
+	pub challenges: usize,
+	/// How often a challenge of this bot caught another bot bluffing
+	pub successful_challenges: usize,
+}
+
+impl ClaimProfile {
+	fn new(name: String, cards: Vec<Card>) -> Self {
+		Self {
+			name,
+			claims: cards.into_iter().map(|card| (card, 0)).collect(),
+			counters: vec![
+				(Counter::Assassination, 0),
+				(Counter::ForeignAid, 0),
+				(Counter::Stealing, 0),
+			],
+			challenged: 0,
+			caught_bluffing: 0,
+			survived_challenges: 0,
+			challenges: 0,
+			successful_challenges: 0,
+		}
+	}
+
+	/// How often this bot claimed the card with an action.
+	pub fn claimed(&self, card: Card) -> usize {
+		self
+			.claims
+			.iter()
+			.find(|(claimed_card, _)| *claimed_card == card)
+			.map_or(0, |(_, count)| *count)
+	}
+
+	/// How often this bot played the counter.
+	pub fn countered(&self, counter: Counter) -> usize {
+		self
+			.counters
+			.iter()
+			.find(|(played_counter, _)| *played_counter == counter)
+			.map_or(0, |(_, count)| *count)
+	}
+}
+
+/// The profile of each opponent seated at this game in seat order.
+///
+/// A challenge is resolved by the card loss that follows it: when the
+/// challenged bot loses a card it was caught bluffing, otherwise the challenge
+/// failed.
+pub fn profiles(context: &Context) -> Vec<ClaimProfile> {
+	let mut profiles = context
+		.seats
+		.iter()
+		.filter(|name| **name != context.name)
+		.map(|name| ClaimProfile::new(name.clone(), context.roles.cards()))
+		.collect::<Vec<ClaimProfile>>();
+
+	// The challenger and the challenged bot of a challenge waiting for its card
+	// loss
+	let mut open_challenge: Option<(String, String)> = None;
+
+	for event in context.history.iter() {
+		match event {
+			History::ActionAssassination { by, .. } => count_claim(
+				&mut profiles,
+				by,
+				context.roles.claimed_card(&Action::Assassination(String::new())),
+			),
+			History::ActionSwapping { by } => count_claim(
+				&mut profiles,
+				by,
+				context.roles.claimed_card(&Action::Swapping),
+			),
+			History::ActionStealing { by, .. } => count_claim(
+				&mut profiles,
+				by,
+				context.roles.claimed_card(&Action::Stealing(String::new())),
+			),
+			History::ActionTax { by } => {
+				count_claim(&mut profiles, by, context.roles.claimed_card(&Action::Tax))
+			},
+			History::ActionExamine { by, .. } => count_claim(
+				&mut profiles,
+				by,
+				context.roles.claimed_card(&Action::Examine(String::new())),
+			),
+			History::CounterAssassination { by, .. } => {
+				count_counter(&mut profiles, by, Counter::Assassination)
+			},
+			History::CounterForeignAid { by, .. } => {
+				count_counter(&mut profiles, by, Counter::ForeignAid)
+			},
+			History::CounterStealing { by, .. } => {
+				count_counter(&mut profiles, by, Counter::Stealing)
+			},
+			History::ChallengeAssassin { by, target }
+			| History::ChallengeAmbassador { by, target }
+			| History::ChallengeCaptain { by, target }
+			| History::ChallengeDuke { by, target }
+			| History::ChallengeEmbezzlement { by, target }
+			| History::ChallengeInquisitor { by, target }
+			| History::ChallengeContessa { by, target }
+			| History::CounterChallengeContessa { by, target }
+			| History::CounterChallengeDuke { by, target }
+			| History::CounterChallengeCaptainAmbassedor { by, target }
+			| History::CounterChallengeAssassin { by, target } => {
+				if let Some(profile) = profile_of(&mut profiles, by) {
+					profile.challenges += 1;
+				}
+				if let Some(profile) = profile_of(&mut profiles, target) {
+					profile.challenged += 1;
+				}
+				open_challenge = Some((by.clone(), target.clone()));
+			},
+			History::CardLoss { by, .. } => {
+				if let Some((challenger, target)) = open_challenge.take() {
+					if *by == target {
+						if let Some(profile) = profile_of(&mut profiles, &target) {
+							profile.caught_bluffing += 1;
+						}
+						if let Some(profile) = profile_of(&mut profiles, &challenger) {
+							profile.successful_challenges += 1;
+						}
+					} else if let Some(profile) = profile_of(&mut profiles, &target) {
+						profile.survived_challenges += 1;
+					}
+				}
+			},
+			History::ActionCoup { .. }
+			| History::ActionForeignAid { .. }
+			| History::ActionIncome { .. }
+			| History::ActionConversion { .. }
+			| History::ActionEmbezzlement { .. }
+			| History::ActionExamineSwap { .. }
+			| History::Draft { .. } => {},
+		}
+	}
+
+	profiles
+}
+
+fn profile_of<'a>(
+	profiles: &'a mut [ClaimProfile],
+	name: &str,
+) -> Option<&'a mut ClaimProfile> {
+	profiles.iter_mut().find(|profile| profile.name == name)
+}
+
+fn count_claim(profiles: &mut [ClaimProfile], by: &str, card: Option<Card>) {
+	if let (Some(profile), Some(card)) = (profile_of(profiles, by), card) {
+		if let Some((_, count)) =
+			profile.claims.iter_mut().find(|(claimed_card, _)| *claimed_card == card)
+		{
+			*count += 1;
+		}
+	}
+}
+
+fn count_counter(profiles: &mut [ClaimProfile], by: &str, counter: Counter) {
+	if let Some(profile) = profile_of(profiles, by) {
+		if let Some((_, count)) = profile
+			.counters
+			.iter_mut()
+			.find(|(played_counter, _)| *played_counter == counter)
+		{
+			*count += 1;
+		}
+	}
+}
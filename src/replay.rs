@@ -0,0 +1,1187 @@
+//! A [Replay] of a single game that can be saved to a file and played again.
+//!
+//! Once turned on with [Coup::set_replay] the engine records the seed of each
+//! game and every decision the bots made. Playing a replay feeds these
+//! decisions back into the engine instead of asking the bots and fails with a
+//! diff if the game doesn't end up with the same history.
+//!
+//! ```rust
+//! use coup::{bots::RandomBot, replay::Replay, Coup};
+//!
+//! let mut coup_game =
+//!     Coup::new(vec![Box::new(RandomBot), Box::new(RandomBot)]);
+//! coup_game.set_replay(true);
+//! let result = coup_game.play();
+//!
+//! let replay: Replay = coup_game.replay().to_string().parse().unwrap();
+//! assert_eq!(replay.run().unwrap(), result);
+//! ```
+
+use std::{
+	cell::RefCell, collections::VecDeque, fmt, fs, io, path::Path, rc::Rc,
+	str::FromStr,
+};
+
+use crate::{
+	bot::{BotInterface, Context},
+	bots::StaticBot,
+	config::{Config, Handicap, TieBreaker},
+	result::GameResult,
+	role::{Role, RoleSet},
+	Action, Card, Counter, Coup, Deck, History,
+};
+
+/// The version of the replay file format written by this engine.
+pub const REPLAY_VERSION: u32 = 1;
+
+/// The answer of a bot to one of the [BotInterface] methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+	/// [BotInterface::on_choose_starting_card]
+	StartingCard(Card),
+	/// [BotInterface::on_draft]
+	Draft([Card; 2]),
+	/// [BotInterface::on_turn]
+	Turn(Action),
+	/// [BotInterface::on_auto_coup]
+	AutoCoup(String),
+	/// [BotInterface::on_challenge_action_round]
+	ChallengeAction(bool),
+	/// [BotInterface::on_challenge_embezzlement]
+	ChallengeEmbezzlement(bool),
+	/// [BotInterface::on_counter]
+	Counter(bool),
+	/// [BotInterface::on_challenge_counter_round]
+	ChallengeCounter(bool),
+	/// [BotInterface::on_swapping_cards]
+	SwappingCards([Card; 2]),
+	/// [BotInterface::on_swapping_card]
+	SwappingCard(Card),
+	/// [BotInterface::on_examine]
+	Examine(Card),
+	/// [BotInterface::on_examined_card]
+	ExaminedCard(bool),
+	/// [BotInterface::on_card_loss]
+	CardLoss(Card),
+}
+
+/// Everything needed to play a game again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+	/// The version of the file format this replay was read from
+	pub version: u32,
+	/// The seed the engine shuffled the bots and the deck with
+	pub seed: u64,
+	/// The configuration the game was played with
+	pub config: Config,
+	/// The names of all bots in the order they were passed into the engine
+	pub bots: Vec<String>,
+	/// The names of all bots seated at the game in seat order
+	pub seats: Vec<String>,
+	/// Each decision in the order the bots made them
+	pub decisions: Vec<(String, Decision)>,
+	/// The history of the game
+	pub history: Vec<History>,
+}
+
+/// Why a replay couldn't be read or played.
+#[derive(Debug)]
+pub enum ReplayError {
+	/// The replay file couldn't be read or written
+	Io(io::Error),
+	/// The replay file was written in a version this engine can't read
+	Version(u32),
+	/// A line of the replay file couldn't be read
+	Parse {
+		/// The line number starting at 1
+		line: usize,
+		/// What was wrong with the line
+		message: String,
+	},
+	/// The configuration of the replay can't be played by this engine
+	Config(String),
+	/// Playing the replay didn't end up with the recorded game
+	Mismatch(String),
+}
+
+impl fmt::Display for ReplayError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ReplayError::Io(error) => write!(f, "Could not access replay: {}", error),
+			ReplayError::Version(version) => write!(
+				f,
+				"Replay version {} is not supported, this engine reads version {}",
+				version, REPLAY_VERSION
+			),
+			ReplayError::Parse { line, message } => {
+				write!(f, "Replay line {}: {}", line, message)
+			},
+			ReplayError::Config(message) => {
+				write!(f, "Replay can't be played: {}", message)
+			},
+			ReplayError::Mismatch(diff) => {
+				write!(f, "Replay does not match the recorded game\n{}", diff)
+			},
+		}
+	}
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<io::Error> for ReplayError {
+	fn from(error: io::Error) -> Self {
+		ReplayError::Io(error)
+	}
+}
+
+impl Replay {
+	/// Write this replay to a file.
+	pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ReplayError> {
+		fs::write(path, self.to_string())?;
+		Ok(())
+	}
+
+	/// Read a replay from a file.
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+		fs::read_to_string(path)?.parse()
+	}
+
+	/// Play the game again by feeding the recorded decisions into the engine.
+	/// Returns the result of the game or a diff between the recorded and the
+	/// replayed game if they don't match.
+	pub fn run(&self) -> Result<GameResult, ReplayError> {
+		self.replayed(false).map(|(result, _)| result)
+	}
+
+	// The engine after playing the replay, recording the table before each
+	// event for the viewer
+	pub(crate) fn replayed(
+		&self,
+		record_frames: bool,
+	) -> Result<(GameResult, Coup), ReplayError> {
+		self.config.check(self.bots.len()).map_err(ReplayError::Config)?;
+
+		let decisions = Rc::new(RefCell::new(
+			self.decisions.iter().cloned().collect::<VecDeque<_>>(),
+		));
+		let divergence = Rc::new(RefCell::new(None));
+		let bots = self
+			.bots
+			.iter()
+			.map(|name| {
+				Box::new(ReplayBot {
+					name: name.clone(),
+					decisions: decisions.clone(),
+					divergence: divergence.clone(),
+				}) as Box<dyn BotInterface>
+			})
+			.collect();
+
+		let mut coup = Coup::new_with_config(bots, self.config.clone());
+		coup.log = false;
+		coup.fixed_seed = Some(self.seed);
+		if record_frames {
+			coup.frames = Some(vec![]);
+		}
+		let result = coup.play();
+		let replayed = coup.replay();
+
+		let mut problems = Vec::new();
+		if let Some(divergence) = divergence.borrow_mut().take() {
+			problems.push(divergence);
+		}
+		if !decisions.borrow().is_empty() {
+			problems.push(format!(
+				"{} recorded decisions were never asked for",
+				decisions.borrow().len()
+			));
+		}
+		if replayed.seats != self.seats {
+			problems.push(format!(
+				"seats differ\n- {:?}\n+ {:?}",
+				self.seats, replayed.seats
+			));
+		}
+		if let Some(diff) = diff(&self.history, &replayed.history) {
+			problems.push(diff);
+		}
+
+		if problems.is_empty() {
+			Ok((result, coup))
+		} else {
+			Err(ReplayError::Mismatch(problems.join("\n")))
+		}
+	}
+}
+
+// The first event where two histories differ with a bit of context around it
+fn diff(expected: &[History], actual: &[History]) -> Option<String> {
+	let index = (0..expected.len().max(actual.len()))
+		.find(|index| expected.get(*index) != actual.get(*index))?;
+
+	let mut lines = vec![format!("history differs at event {}", index)];
+	for (before, event) in
+		expected.iter().enumerate().take(index).skip(index.saturating_sub(3))
+	{
+		lines.push(format!("  {:>5} {:?}", before, event));
+	}
+	for (offset, event) in expected.iter().skip(index).take(3).enumerate() {
+		lines.push(format!("- {:>5} {:?}", index + offset, event));
+	}
+	for (offset, event) in actual.iter().skip(index).take(3).enumerate() {
+		lines.push(format!("+ {:>5} {:?}", index + offset, event));
+	}
+	Some(lines.join("\n"))
+}
+
+// The decisions of all bots of a game by name, `None` while not recording
+pub(crate) type Decisions = Rc<RefCell<Option<Vec<(String, Decision)>>>>;
+
+// Wraps each bot to record its decisions in the order they were made
+pub(crate) struct Recorder {
+	pub(crate) name: String,
+	pub(crate) decisions: Decisions,
+	pub(crate) bot: Box<dyn BotInterface>,
+}
+
+impl Recorder {
+	// Only while recording is turned on with Coup::set_replay
+	fn record(&self, decision: Decision) {
+		if let Some(decisions) = self.decisions.borrow_mut().as_mut() {
+			decisions.push((self.name.clone(), decision));
+		}
+	}
+}
+
+impl BotInterface for Recorder {
+	fn get_name(&self) -> String {
+		self.bot.get_name()
+	}
+
+	fn on_choose_starting_card(
+		&self,
+		cards: Vec<Card>,
+		context: &Context,
+	) -> Card {
+		let card = self.bot.on_choose_starting_card(cards, context);
+		self.record(Decision::StartingCard(card));
+		card
+	}
+
+	fn on_draft(&self, cards: Vec<Card>, context: &Context) -> [Card; 2] {
+		let cards = self.bot.on_draft(cards, context);
+		self.record(Decision::Draft(cards));
+		cards
+	}
+
+	fn on_turn(&self, context: &Context) -> Action {
+		let action = self.bot.on_turn(context);
+		self.record(Decision::Turn(action.clone()));
+		action
+	}
+
+	fn on_auto_coup(&self, context: &Context) -> String {
+		let target = self.bot.on_auto_coup(context);
+		self.record(Decision::AutoCoup(target.clone()));
+		target
+	}
+
+	fn on_challenge_action_round(
+		&self,
+		action: &Action,
+		by: String,
+		context: &Context,
+	) -> bool {
+		let challenge = self.bot.on_challenge_action_round(action, by, context);
+		self.record(Decision::ChallengeAction(challenge));
+		challenge
+	}
+
+	fn on_challenge_embezzlement(&self, by: String, context: &Context) -> bool {
+		let challenge = self.bot.on_challenge_embezzlement(by, context);
+		self.record(Decision::ChallengeEmbezzlement(challenge));
+		challenge
+	}
+
+	fn on_counter(&self, action: &Action, by: String, context: &Context) -> bool {
+		let counter = self.bot.on_counter(action, by, context);
+		self.record(Decision::Counter(counter));
+		counter
+	}
+
+	fn on_challenge_counter_round(
+		&self,
+		action: &Action,
+		by: String,
+		context: &Context,
+	) -> bool {
+		let challenge = self.bot.on_challenge_counter_round(action, by, context);
+		self.record(Decision::ChallengeCounter(challenge));
+		challenge
+	}
+
+	fn on_swapping_cards(
+		&self,
+		new_cards: [Card; 2],
+		context: &Context,
+	) -> [Card; 2] {
+		let cards = self.bot.on_swapping_cards(new_cards, context);
+		self.record(Decision::SwappingCards(cards));
+		cards
+	}
+
+	fn on_swapping_card(&self, new_card: Card, context: &Context) -> Card {
+		let card = self.bot.on_swapping_card(new_card, context);
+		self.record(Decision::SwappingCard(card));
+		card
+	}
+
+	fn on_examine(&self, by: String, context: &Context) -> Card {
+		let card = self.bot.on_examine(by, context);
+		self.record(Decision::Examine(card));
+		card
+	}
+
+	fn on_examined_card(
+		&self,
+		card: Card,
+		target: String,
+		context: &Context,
+	) -> bool {
+		let swap = self.bot.on_examined_card(card, target, context);
+		self.record(Decision::ExaminedCard(swap));
+		swap
+	}
+
+	fn on_card_loss(&self, context: &Context) -> Card {
+		let card = self.bot.on_card_loss(context);
+		self.record(Decision::CardLoss(card));
+		card
+	}
+}
+
+// Answers with the recorded decisions and falls back to the StaticBot once the
+// game went somewhere the recording didn't
+struct ReplayBot {
+	name: String,
+	decisions: Rc<RefCell<VecDeque<(String, Decision)>>>,
+	divergence: Rc<RefCell<Option<String>>>,
+}
+
+impl ReplayBot {
+	fn next(&self, asked: &str) -> Option<Decision> {
+		if self.divergence.borrow().is_some() {
+			return None;
+		}
+
+		let next = self.decisions.borrow_mut().pop_front();
+		match next {
+			Some((name, decision))
+				if name == self.name && decision_name(&decision) == asked =>
+			{
+				Some(decision)
+			},
+			Some((name, decision)) => {
+				*self.divergence.borrow_mut() = Some(format!(
+					"{} was asked for {} but the next recorded decision is {:?} by {}",
+					self.name, asked, decision, name
+				));
+				None
+			},
+			None => {
+				*self.divergence.borrow_mut() = Some(format!(
+					"{} was asked for {} after all recorded decisions were used",
+					self.name, asked
+				));
+				None
+			},
+		}
+	}
+}
+
+impl BotInterface for ReplayBot {
+	fn get_name(&self) -> String {
+		self.name.clone()
+	}
+
+	fn on_choose_starting_card(
+		&self,
+		cards: Vec<Card>,
+		context: &Context,
+	) -> Card {
+		match self.next("StartingCard") {
+			Some(Decision::StartingCard(card)) => card,
+			_ => StaticBot.on_choose_starting_card(cards, context),
+		}
+	}
+
+	fn on_draft(&self, cards: Vec<Card>, context: &Context) -> [Card; 2] {
+		match self.next("Draft") {
+			Some(Decision::Draft(cards)) => cards,
+			_ => StaticBot.on_draft(cards, context),
+		}
+	}
+
+	fn on_turn(&self, context: &Context) -> Action {
+		match self.next("Turn") {
+			Some(Decision::Turn(action)) => action,
+			_ => StaticBot.on_turn(context),
+		}
+	}
+
+	fn on_auto_coup(&self, context: &Context) -> String {
+		match self.next("AutoCoup") {
+			Some(Decision::AutoCoup(target)) => target,
+			_ => StaticBot.on_auto_coup(context),
+		}
+	}
+
+	fn on_challenge_action_round(
+		&self,
+		action: &Action,
+		by: String,
+		context: &Context,
+	) -> bool {
+		match self.next("ChallengeAction") {
+			Some(Decision::ChallengeAction(challenge)) => challenge,
+			_ => StaticBot.on_challenge_action_round(action, by, context),
+		}
+	}
+
+	fn on_challenge_embezzlement(&self, by: String, context: &Context) -> bool {
+		match self.next("ChallengeEmbezzlement") {
+			Some(Decision::ChallengeEmbezzlement(challenge)) => challenge,
+			_ => StaticBot.on_challenge_embezzlement(by, context),
+		}
+	}
+
+	fn on_counter(&self, action: &Action, by: String, context: &Context) -> bool {
+		match self.next("Counter") {
+			Some(Decision::Counter(counter)) => counter,
+			_ => StaticBot.on_counter(action, by, context),
+		}
+	}
+
+	fn on_challenge_counter_round(
+		&self,
+		action: &Action,
+		by: String,
+		context: &Context,
+	) -> bool {
+		match self.next("ChallengeCounter") {
+			Some(Decision::ChallengeCounter(challenge)) => challenge,
+			_ => StaticBot.on_challenge_counter_round(action, by, context),
+		}
+	}
+
+	fn on_swapping_cards(
+		&self,
+		new_cards: [Card; 2],
+		context: &Context,
+	) -> [Card; 2] {
+		match self.next("SwappingCards") {
+			Some(Decision::SwappingCards(cards)) => cards,
+			_ => StaticBot.on_swapping_cards(new_cards, context),
+		}
+	}
+
+	fn on_swapping_card(&self, new_card: Card, context: &Context) -> Card {
+		match self.next("SwappingCard") {
+			Some(Decision::SwappingCard(card)) => card,
+			_ => StaticBot.on_swapping_card(new_card, context),
+		}
+	}
+
+	fn on_examine(&self, by: String, context: &Context) -> Card {
+		match self.next("Examine") {
+			Some(Decision::Examine(card)) => card,
+			_ => StaticBot.on_examine(by, context),
+		}
+	}
+
+	fn on_examined_card(
+		&self,
+		card: Card,
+		target: String,
+		context: &Context,
+	) -> bool {
+		match self.next("ExaminedCard") {
+			Some(Decision::ExaminedCard(swap)) => swap,
+			_ => StaticBot.on_examined_card(card, target, context),
+		}
+	}
+
+	fn on_card_loss(&self, context: &Context) -> Card {
+		match self.next("CardLoss") {
+			Some(Decision::CardLoss(card)) => card,
+			_ => StaticBot.on_card_loss(context),
+		}
+	}
+}
+
+fn decision_name(decision: &Decision) -> &'static str {
+	match decision {
+		Decision::StartingCard(_) => "StartingCard",
+		Decision::Draft(_) => "Draft",
+		Decision::Turn(_) => "Turn",
+		Decision::AutoCoup(_) => "AutoCoup",
+		Decision::ChallengeAction(_) => "ChallengeAction",
+		Decision::ChallengeEmbezzlement(_) => "ChallengeEmbezzlement",
+		Decision::Counter(_) => "Counter",
+		Decision::ChallengeCounter(_) => "ChallengeCounter",
+		Decision::SwappingCards(_) => "SwappingCards",
+		Decision::SwappingCard(_) => "SwappingCard",
+		Decision::Examine(_) => "Examine",
+		Decision::ExaminedCard(_) => "ExaminedCard",
+		Decision::CardLoss(_) => "CardLoss",
+	}
+}
+
+// The file format is made of lines of space separated words where names are
+// quoted and lines starting with a # are comments. It starts with the version,
+// then the seed and each field of the config followed by the bots, seats,
+// decisions and history in order.
+impl fmt::Display for Replay {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let config = &self.config;
+		writeln!(f, "coup-replay {}", REPLAY_VERSION)?;
+		writeln!(f, "game_seed {}", self.seed)?;
+		writeln!(f, "reformation {}", config.reformation)?;
+		writeln!(f, "deck {:?}", config.deck)?;
+		if let Some(roles) = &config.roles {
+			for role in roles.roles.iter() {
+				writeln!(f, "role {}", write_role(role))?;
+			}
+		}
+		writeln!(f, "two_player {}", config.two_player)?;
+		writeln!(f, "draft {}", write_option(config.draft))?;
+		writeln!(f, "max_players {}", config.max_players)?;
+		writeln!(f, "max_moves {}", config.max_moves)?;
+		writeln!(
+			f,
+			"stalemate_repetitions {}",
+			write_option(config.stalemate_repetitions)
+		)?;
+		writeln!(f, "stalemate_rounds {}", write_option(config.stalemate_rounds))?;
+		writeln!(
+			f,
+			"tie_breakers{}",
+			write_list(&config.tie_breakers, |t| format!("{:?}", t))
+		)?;
+		match &config.teams {
+			Some(teams) => {
+				writeln!(f, "teams{}", write_list(teams, |team| team.to_string()))?
+			},
+			None => writeln!(f, "teams none")?,
+		}
+		writeln!(f, "friendly_fire {}", config.friendly_fire)?;
+		match &config.handicaps {
+			Some(handicaps) => writeln!(
+				f,
+				"handicaps{}",
+				write_list(handicaps, |handicap| format!(
+					"{}:{}",
+					handicap.coins, handicap.cards
+				))
+			)?,
+			None => writeln!(f, "handicaps none")?,
+		}
+		writeln!(f, "seed {}", write_option(config.seed))?;
+		for name in self.bots.iter() {
+			writeln!(f, "bot {}", quote(name))?;
+		}
+		for name in self.seats.iter() {
+			writeln!(f, "seat {}", quote(name))?;
+		}
+		for (name, decision) in self.decisions.iter() {
+			writeln!(f, "decision {} {}", quote(name), write_decision(decision))?;
+		}
+		for event in self.history.iter() {
+			writeln!(f, "history {}", write_history(event))?;
+		}
+		Ok(())
+	}
+}
+
+impl FromStr for Replay {
+	type Err = ReplayError;
+
+	fn from_str(text: &str) -> Result<Self, Self::Err> {
+		let mut replay = Replay {
+			version: 0,
+			seed: 0,
+			config: Config::default(),
+			bots: vec![],
+			seats: vec![],
+			decisions: vec![],
+			history: vec![],
+		};
+		let mut roles = Vec::new();
+
+		for (index, line) in text.lines().enumerate() {
+			let parse_error = |message: String| ReplayError::Parse {
+				line: index + 1,
+				message,
+			};
+			// Comments can hold anything else worth knowing about the game
+			if line.starts_with('#') {
+				continue;
+			}
+			let mut fields = Fields::new(line).map_err(parse_error)?;
+			let Some(key) = fields.next() else {
+				continue;
+			};
+
+			if replay.version == 0 {
+				if key != "coup-replay" {
+					return Err(parse_error(String::from("not a coup replay")));
+				}
+				replay.version = fields.value().map_err(parse_error)?;
+				if replay.version != REPLAY_VERSION {
+					return Err(ReplayError::Version(replay.version));
+				}
+				continue;
+			}
+
+			parse_line(&mut replay, &mut roles, &key, &mut fields)
+				.and_then(|_| fields.end())
+				.map_err(parse_error)?;
+		}
+
+		if replay.version == 0 {
+			return Err(ReplayError::Parse {
+				line: 1,
+				message: String::from("not a coup replay"),
+			});
+		}
+		if !roles.is_empty() {
+			replay.config.roles = Some(RoleSet { roles });
+		}
+		Ok(replay)
+	}
+}
+
+fn parse_line(
+	replay: &mut Replay,
+	roles: &mut Vec<Role>,
+	key: &str,
+	fields: &mut Fields,
+) -> Result<(), String> {
+	let config = &mut replay.config;
+	match key {
+		"game_seed" => replay.seed = fields.value()?,
+		"reformation" => config.reformation = fields.value()?,
+		"deck" => config.deck = parse_deck(&fields.word()?)?,
+		"role" => roles.push(parse_role(fields)?),
+		"two_player" => config.two_player = fields.value()?,
+		"draft" => config.draft = fields.option()?,
+		"max_players" => config.max_players = fields.value()?,
+		"max_moves" => config.max_moves = fields.value()?,
+		"stalemate_repetitions" => {
+			config.stalemate_repetitions = fields.option()?
+		},
+		"stalemate_rounds" => config.stalemate_rounds = fields.option()?,
+		"tie_breakers" => {
+			config.tie_breakers = fields
+				.rest()
+				.iter()
+				.map(|word| parse_tie_breaker(word))
+				.collect::<Result<_, _>>()?
+		},
+		"teams" => {
+			config.teams = fields.list(|word| {
+				word.parse().map_err(|_| format!("invalid team {}", word))
+			})?
+		},
+		"friendly_fire" => config.friendly_fire = fields.value()?,
+		"handicaps" => config.handicaps = fields.list(parse_handicap)?,
+		"seed" => config.seed = fields.option()?,
+		"bot" => replay.bots.push(fields.word()?),
+		"seat" => replay.seats.push(fields.word()?),
+		"decision" => {
+			let name = fields.word()?;
+			replay.decisions.push((name, parse_decision(fields)?));
+		},
+		"history" => replay.history.push(parse_history(fields)?),
+		_ => return Err(format!("unknown key {}", key)),
+	}
+	Ok(())
+}
+
+// The words of a line where quoted words may contain spaces
+struct Fields {
+	words: VecDeque<String>,
+}
+
+impl Fields {
+	fn new(line: &str) -> Result<Self, String> {
+		let mut words = VecDeque::new();
+		let mut chars = line.chars().peekable();
+		while let Some(char) = chars.next() {
+			match char {
+				' ' => {},
+				'"' => {
+					let mut word = String::new();
+					loop {
+						match chars.next() {
+							Some('"') => break,
+							Some('\\') => match chars.next() {
+								Some('n') => word.push('\n'),
+								Some(escaped) => word.push(escaped),
+								None => return Err(String::from("unfinished quote")),
+							},
+							Some(char) => word.push(char),
+							None => return Err(String::from("unfinished quote")),
+						}
+					}
+					words.push_back(word);
+				},
+				_ => {
+					let mut word = String::from(char);
+					while let Some(char) = chars.next_if(|char| *char != ' ') {
+						word.push(char);
+					}
+					words.push_back(word);
+				},
+			}
+		}
+		Ok(Self { words })
+	}
+
+	fn next(&mut self) -> Option<String> {
+		self.words.pop_front()
+	}
+
+	fn word(&mut self) -> Result<String, String> {
+		self.next().ok_or_else(|| String::from("missing value"))
+	}
+
+	fn value<T: FromStr>(&mut self) -> Result<T, String> {
+		let word = self.word()?;
+		word.parse().map_err(|_| format!("invalid value {}", word))
+	}
+
+	fn option<T: FromStr>(&mut self) -> Result<Option<T>, String> {
+		match self.word()?.as_str() {
+			"none" => Ok(None),
+			word => {
+				word.parse().map(Some).map_err(|_| format!("invalid value {}", word))
+			},
+		}
+	}
+
+	fn card(&mut self) -> Result<Card, String> {
+		parse_card(&self.word()?)
+	}
+
+	fn rest(&mut self) -> Vec<String> {
+		self.words.drain(..).collect()
+	}
+
+	// A list that can be none as opposed to an empty list
+	fn list<T>(
+		&mut self,
+		parse: impl Fn(&str) -> Result<T, String>,
+	) -> Result<Option<Vec<T>>, String> {
+		let words = self.rest();
+		if words.len() == 1 && words[0] == "none" {
+			return Ok(None);
+		}
+		words.iter().map(|word| parse(word)).collect::<Result<_, _>>().map(Some)
+	}
+
+	fn end(&self) -> Result<(), String> {
+		match self.words.front() {
+			Some(word) => Err(format!("unexpected value {}", word)),
+			None => Ok(()),
+		}
+	}
+}
+
+fn quote(name: &str) -> String {
+	format!(
+		"\"{}\"",
+		name.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+	)
+}
+
+fn write_option<T: ToString>(value: Option<T>) -> String {
+	value.map_or(String::from("none"), |value| value.to_string())
+}
+
+fn write_list<T>(items: &[T], write: impl Fn(&T) -> String) -> String {
+	items.iter().map(|item| format!(" {}", write(item))).collect()
+}
+
+fn write_action(action: &Action) -> String {
+	match action {
+		Action::Assassination(target) => format!("Assassination {}", quote(target)),
+		Action::Coup(target) => format!("Coup {}", quote(target)),
+		Action::ForeignAid => String::from("ForeignAid"),
+		Action::Swapping => String::from("Swapping"),
+		Action::Income => String::from("Income"),
+		Action::Stealing(target) => format!("Stealing {}", quote(target)),
+		Action::Tax => String::from("Tax"),
+		Action::Conversion(target) => format!("Conversion {}", quote(target)),
+		Action::Embezzlement => String::from("Embezzlement"),
+		Action::Examine(target) => format!("Examine {}", quote(target)),
+	}
+}
+
+fn parse_action(fields: &mut Fields) -> Result<Action, String> {
+	let name = fields.word()?;
+	Ok(match name.as_str() {
+		"Assassination" => Action::Assassination(fields.word()?),
+		"Coup" => Action::Coup(fields.word()?),
+		"ForeignAid" => Action::ForeignAid,
+		"Swapping" => Action::Swapping,
+		"Income" => Action::Income,
+		"Stealing" => Action::Stealing(fields.word()?),
+		"Tax" => Action::Tax,
+		"Conversion" => Action::Conversion(fields.word()?),
+		"Embezzlement" => Action::Embezzlement,
+		"Examine" => Action::Examine(fields.word()?),
+		_ => return Err(format!("unknown action {}", name)),
+	})
+}
+
+// Actions of a role don't have a target so they are written without one
+fn parse_role_action(name: &str) -> Result<Action, String> {
+	Ok(match name {
+		"Assassination" => Action::Assassination(String::new()),
+		"Coup" => Action::Coup(String::new()),
+		"ForeignAid" => Action::ForeignAid,
+		"Swapping" => Action::Swapping,
+		"Income" => Action::Income,
+		"Stealing" => Action::Stealing(String::new()),
+		"Tax" => Action::Tax,
+		"Conversion" => Action::Conversion(String::new()),
+		"Embezzlement" => Action::Embezzlement,
+		"Examine" => Action::Examine(String::new()),
+		_ => return Err(format!("unknown action {}", name)),
+	})
+}
+
+fn action_name(action: &Action) -> String {
+	write_action(action).split(' ').next().unwrap_or_default().to_string()
+}
+
+fn write_role(role: &Role) -> String {
+	format!(
+		"{:?} actions{} exchange {} disclaimed{} blocks{}",
+		role.card,
+		write_list(&role.actions, |(action, cost)| format!(
+			"{}:{}",
+			action_name(action),
+			cost
+		)),
+		role.exchange_cards,
+		write_list(&role.disclaimed_actions, action_name),
+		write_list(&role.blocks, |counter| format!("{:?}", counter)),
+	)
+}
+
+fn parse_role(fields: &mut Fields) -> Result<Role, String> {
+	let mut role = Role {
+		card: fields.card()?,
+		actions: vec![],
+		exchange_cards: 0,
+		disclaimed_actions: vec![],
+		blocks: vec![],
+	};
+
+	let mut section = String::new();
+	for word in fields.rest() {
+		match (section.as_str(), word.as_str()) {
+			(_, "actions" | "exchange" | "disclaimed" | "blocks") => section = word,
+			("exchange", _) => {
+				role.exchange_cards =
+					word.parse().map_err(|_| format!("invalid exchange {}", word))?
+			},
+			("actions", _) => {
+				let (name, cost) = word
+					.split_once(':')
+					.ok_or_else(|| format!("invalid role action {}", word))?;
+				let cost =
+					cost.parse().map_err(|_| format!("invalid cost {}", cost))?;
+				role.actions.push((parse_role_action(name)?, cost));
+			},
+			("disclaimed", _) => {
+				role.disclaimed_actions.push(parse_role_action(&word)?)
+			},
+			("blocks", _) => role.blocks.push(parse_counter(&word)?),
+			_ => return Err(format!("unexpected value {}", word)),
+		}
+	}
+	Ok(role)
+}
+
+fn parse_card(name: &str) -> Result<Card, String> {
+	match name {
+		"Ambassador" => Ok(Card::Ambassador),
+		"Inquisitor" => Ok(Card::Inquisitor),
+		"Assassin" => Ok(Card::Assassin),
+		"Captain" => Ok(Card::Captain),
+		"Contessa" => Ok(Card::Contessa),
+		"Duke" => Ok(Card::Duke),
+		_ => Err(format!("unknown card {}", name)),
+	}
+}
+
+fn parse_counter(name: &str) -> Result<Counter, String> {
+	match name {
+		"Assassination" => Ok(Counter::Assassination),
+		"ForeignAid" => Ok(Counter::ForeignAid),
+		"Stealing" => Ok(Counter::Stealing),
+		_ => Err(format!("unknown counter {}", name)),
+	}
+}
+
+fn parse_deck(name: &str) -> Result<Deck, String> {
+	match name {
+		"Classic" => Ok(Deck::Classic),
+		"Inquisitor" => Ok(Deck::Inquisitor),
+		_ => Err(format!("unknown deck {}", name)),
+	}
+}
+
+fn parse_tie_breaker(name: &str) -> Result<TieBreaker, String> {
+	match name {
+		"MostInfluence" => Ok(TieBreaker::MostInfluence),
+		"MostCoins" => Ok(TieBreaker::MostCoins),
+		"Shared" => Ok(TieBreaker::Shared),
+		_ => Err(format!("unknown tie-breaker {}", name)),
+	}
+}
+
+fn parse_handicap(word: &str) -> Result<Handicap, String> {
+	let invalid = || format!("invalid handicap {}", word);
+	let (coins, cards) = word.split_once(':').ok_or_else(invalid)?;
+	Ok(Handicap {
+		coins: coins.parse().map_err(|_| invalid())?,
+		cards: cards.parse().map_err(|_| invalid())?,
+	})
+}
+
+fn write_decision(decision: &Decision) -> String {
+	let value = match decision {
+		Decision::StartingCard(card)
+		| Decision::SwappingCard(card)
+		| Decision::Examine(card)
+		| Decision::CardLoss(card) => format!("{:?}", card),
+		Decision::Draft([first, second])
+		| Decision::SwappingCards([first, second]) => {
+			format!("{:?} {:?}", first, second)
+		},
+		Decision::Turn(action) => write_action(action),
+		Decision::AutoCoup(target) => quote(target),
+		Decision::ChallengeAction(answer)
+		| Decision::ChallengeEmbezzlement(answer)
+		| Decision::Counter(answer)
+		| Decision::ChallengeCounter(answer)
+		| Decision::ExaminedCard(answer) => answer.to_string(),
+	};
+	format!("{} {}", decision_name(decision), value)
+}
+
+fn parse_decision(fields: &mut Fields) -> Result<Decision, String> {
+	let name = fields.word()?;
+	Ok(match name.as_str() {
+		"StartingCard" => Decision::StartingCard(fields.card()?),
+		"Draft" => Decision::Draft([fields.card()?, fields.card()?]),
+		"Turn" => Decision::Turn(parse_action(fields)?),
+		"AutoCoup" => Decision::AutoCoup(fields.word()?),
+		"ChallengeAction" => Decision::ChallengeAction(fields.value()?),
+		"ChallengeEmbezzlement" => Decision::ChallengeEmbezzlement(fields.value()?),
+		"Counter" => Decision::Counter(fields.value()?),
+		"ChallengeCounter" => Decision::ChallengeCounter(fields.value()?),
+		"SwappingCards" => {
+			Decision::SwappingCards([fields.card()?, fields.card()?])
+		},
+		"SwappingCard" => Decision::SwappingCard(fields.card()?),
+		"Examine" => Decision::Examine(fields.card()?),
+		"ExaminedCard" => Decision::ExaminedCard(fields.value()?),
+		"CardLoss" => Decision::CardLoss(fields.card()?),
+		_ => return Err(format!("unknown decision {}", name)),
+	})
+}
+
+fn write_history(event: &History) -> String {
+	match event {
+		History::ActionAssassination { by, target } => {
+			format!("ActionAssassination {} {}", quote(by), quote(target))
+		},
+		History::ActionCoup { by, target } => {
+			format!("ActionCoup {} {}", quote(by), quote(target))
+		},
+		History::ActionForeignAid { by } => {
+			format!("ActionForeignAid {}", quote(by))
+		},
+		History::ActionSwapping { by } => format!("ActionSwapping {}", quote(by)),
+		History::ActionIncome { by } => format!("ActionIncome {}", quote(by)),
+		History::ActionStealing { by, target } => {
+			format!("ActionStealing {} {}", quote(by), quote(target))
+		},
+		History::ActionTax { by } => format!("ActionTax {}", quote(by)),
+		History::ActionConversion { by, target } => {
+			format!("ActionConversion {} {}", quote(by), quote(target))
+		},
+		History::ActionEmbezzlement { by } => {
+			format!("ActionEmbezzlement {}", quote(by))
+		},
+		History::ActionExamine { by, target } => {
+			format!("ActionExamine {} {}", quote(by), quote(target))
+		},
+		History::ActionExamineSwap { by, target } => {
+			format!("ActionExamineSwap {} {}", quote(by), quote(target))
+		},
+		History::ChallengeAssassin { by, target } => {
+			format!("ChallengeAssassin {} {}", quote(by), quote(target))
+		},
+		History::ChallengeAmbassador { by, target } => {
+			format!("ChallengeAmbassador {} {}", quote(by), quote(target))
+		},
+		History::ChallengeCaptain { by, target } => {
+			format!("ChallengeCaptain {} {}", quote(by), quote(target))
+		},
+		History::ChallengeDuke { by, target } => {
+			format!("ChallengeDuke {} {}", quote(by), quote(target))
+		},
+		History::ChallengeEmbezzlement { by, target } => {
+			format!("ChallengeEmbezzlement {} {}", quote(by), quote(target))
+		},
+		History::ChallengeInquisitor { by, target } => {
+			format!("ChallengeInquisitor {} {}", quote(by), quote(target))
+		},
+		History::ChallengeContessa { by, target } => {
+			format!("ChallengeContessa {} {}", quote(by), quote(target))
+		},
+		History::CounterAssassination { by, target } => {
+			format!("CounterAssassination {} {}", quote(by), quote(target))
+		},
+		History::CounterForeignAid { by, target } => {
+			format!("CounterForeignAid {} {}", quote(by), quote(target))
+		},
+		History::CounterStealing { by, target } => {
+			format!("CounterStealing {} {}", quote(by), quote(target))
+		},
+		History::CounterChallengeContessa { by, target } => {
+			format!("CounterChallengeContessa {} {}", quote(by), quote(target))
+		},
+		History::CounterChallengeDuke { by, target } => {
+			format!("CounterChallengeDuke {} {}", quote(by), quote(target))
+		},
+		History::CounterChallengeCaptainAmbassedor { by, target } => format!(
+			"CounterChallengeCaptainAmbassedor {} {}",
+			quote(by),
+			quote(target)
+		),
+		History::CounterChallengeAssassin { by, target } => {
+			format!("CounterChallengeAssassin {} {}", quote(by), quote(target))
+		},
+		History::Draft { by } => format!("Draft {}", quote(by)),
+		History::CardLoss { by, card } => {
+			format!("CardLoss {} {:?}", quote(by), card)
+		},
+	}
+}
+
+fn parse_history(fields: &mut Fields) -> Result<History, String> {
+	let name = fields.word()?;
+	let by = fields.word()?;
+	Ok(match name.as_str() {
+		"ActionAssassination" => History::ActionAssassination {
+			by,
+			target: fields.word()?,
+		},
+		"ActionCoup" => History::ActionCoup {
+			by,
+			target: fields.word()?,
+		},
+		"ActionForeignAid" => History::ActionForeignAid { by },
+		"ActionSwapping" => History::ActionSwapping { by },
+		"ActionIncome" => History::ActionIncome { by },
+		"ActionStealing" => History::ActionStealing {
+			by,
+			target: fields.word()?,
+		},
+		"ActionTax" => History::ActionTax { by },
+		"ActionConversion" => History::ActionConversion {
+			by,
+			target: fields.word()?,
+		},
+		"ActionEmbezzlement" => History::ActionEmbezzlement { by },
+		"ActionExamine" => History::ActionExamine {
+			by,
+			target: fields.word()?,
+		},
+		"ActionExamineSwap" => History::ActionExamineSwap {
+			by,
+			target: fields.word()?,
+		},
+		"ChallengeAssassin" => History::ChallengeAssassin {
+			by,
+			target: fields.word()?,
+		},
+		"ChallengeAmbassador" => History::ChallengeAmbassador {
+			by,
+			target: fields.word()?,
+		},
+		"ChallengeCaptain" => History::ChallengeCaptain {
+			by,
+			target: fields.word()?,
+		},
+		"ChallengeDuke" => History::ChallengeDuke {
+			by,
+			target: fields.word()?,
+		},
+		"ChallengeEmbezzlement" => History::ChallengeEmbezzlement {
+			by,
+			target: fields.word()?,
+		},
+		"ChallengeInquisitor" => History::ChallengeInquisitor {
+			by,
+			target: fields.word()?,
+		},
+		"ChallengeContessa" => History::ChallengeContessa {
+			by,
+			target: fields.word()?,
+		},
+		"CounterAssassination" => History::CounterAssassination {
+			by,
+			target: fields.word()?,
+		},
+		"CounterForeignAid" => History::CounterForeignAid {
+			by,
+			target: fields.word()?,
+		},
+		"CounterStealing" => History::CounterStealing {
+			by,
+			target: fields.word()?,
+		},
+		"CounterChallengeContessa" => History::CounterChallengeContessa {
+			by,
+			target: fields.word()?,
+		},
+		"CounterChallengeDuke" => History::CounterChallengeDuke {
+			by,
+			target: fields.word()?,
+		},
+		"CounterChallengeCaptainAmbassedor" => {
+			History::CounterChallengeCaptainAmbassedor {
+				by,
+				target: fields.word()?,
+			}
+		},
+		"CounterChallengeAssassin" => History::CounterChallengeAssassin {
+			by,
+			target: fields.word()?,
+		},
+		"Draft" => History::Draft { by },
+		"CardLoss" => History::CardLoss {
+			by,
+			card: fields.card()?,
+		},
+		_ => return Err(format!("unknown event {}", name)),
+	})
+}
@@ -0,0 +1,111 @@
+//! The [GameResult] of a single game returned by [crate::Coup::play].
+
+use crate::{
+	config::{Handicap, TieBreaker},
+	Card,
+};
+
+/// The outcome of a single game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameResult {
+	/// The names of all bots who won this game
+	pub winners: Vec<String>,
+	/// The amount of moves this game took
+	pub moves: usize,
+	/// Why the game was stopped if it ended in a stalemate
+	pub stalemate: Option<Stalemate>,
+	/// The tie-breaker that decided the game if it ended in a stalemate
+	pub tie_breaker: Option<TieBreaker>,
+	/// The cards each seated bot started the game with in seat order
+	pub starting_hands: Vec<(String, Vec<Card>)>,
+	/// The handicaps of all seated bots that didn't start with the default
+	/// starting conditions
+	pub handicaps: Vec<(String, Handicap)>,
+	/// The teams of all seated bots in team play
+	pub teams: Vec<(String, usize)>,
+	/// The bots that lost all their cards in the order they were eliminated
+	pub eliminations: Vec<Elimination>,
+}
+
+/// A bot losing its last card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elimination {
+	/// The name of the bot that was eliminated
+	pub name: String,
+	/// The bot that took its last card or `None` if the engine took it as a
+	/// penalty
+	pub by: Option<String>,
+	/// The move of the game this bot was eliminated in
+	pub turn: usize,
+}
+
+impl GameResult {
+	/// The names of all bots seated at this game in seat order.
+	pub fn seated(&self) -> Vec<String> {
+		self.starting_hands.iter().map(|(name, _)| name.clone()).collect()
+	}
+
+	/// The sides of this game in seat order: a team in team play or a single bot
+	/// otherwise.
+	pub fn sides(&self) -> Vec<Vec<String>> {
+		let mut sides: Vec<(Option<usize>, Vec<String>)> = Vec::new();
+		for name in self.seated() {
+			let team = self
+				.teams
+				.iter()
+				.find(|(member, _)| *member == name)
+				.map(|(_, team)| *team);
+			match sides
+				.iter_mut()
+				.find(|(side_team, _)| team.is_some() && *side_team == team)
+			{
+				Some((_, side)) => side.push(name),
+				None => sides.push((team, vec![name])),
+			}
+		}
+		sides.into_iter().map(|(_, side)| side).collect()
+	}
+
+	/// The placement of each seated bot in seat order.
+	/// Winners are placed first, bots that lost without being eliminated share
+	/// the next place and eliminated bots are placed by elimination order with
+	/// the first eliminated bot placed last.
+	pub fn placements(&self) -> Vec<(String, usize)> {
+		let players = self.starting_hands.len();
+		let eliminated = self
+			.eliminations
+			.iter()
+			.filter(|elimination| !self.winners.contains(&elimination.name))
+			.map(|elimination| elimination.name.clone())
+			.collect::<Vec<String>>();
+
+		self
+			.seated()
+			.into_iter()
+			.map(|name| {
+				let placement = if self.winners.contains(&name) {
+					1
+				} else {
+					match eliminated.iter().position(|bot| *bot == name) {
+						Some(position) => players - position,
+						None => self.winners.len() + 1,
+					}
+				};
+				(name, placement)
+			})
+			.collect()
+	}
+}
+
+/// The reasons the engine stops a game that doesn't progress.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Stalemate {
+	/// The game reached [crate::config::Config::max_moves].
+	MaxMoves,
+	/// The same public state came up
+	/// [crate::config::Config::stalemate_repetitions] times.
+	Repetition,
+	/// Coins and cards of all bots didn't change over
+	/// [crate::config::Config::stalemate_rounds] rounds.
+	NoProgress,
+}
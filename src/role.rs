@@ -0,0 +1,220 @@
+//! The [Role] registry that decides which [Card] allows which [Action] and
+//! [Counter].
+//!
+//! The engine drives its challenge and counter rounds from the [RoleSet] of
+//! the game so a custom set of roles can be passed in via
+//! [crate::config::Config::roles].
+//!
+//! ```rust
+//! use coup::{
+//!     bots::StaticBot,
+//!     config::Config,
+//!     role::{Role, RoleSet},
+//!     Action, Card, Counter, Coup,
+//! };
+//!
+//! // A homebrew set where the Contessa also blocks foreign aid
+//! let mut roles = RoleSet::classic();
+//! roles.roles[3] = Role {
+//!     card: Card::Contessa,
+//!     actions: vec![],
+//!     exchange_cards: 0,
+//!     disclaimed_actions: vec![],
+//!     blocks: vec![Counter::Assassination, Counter::ForeignAid],
+//! };
+//!
+//! let mut coup_game = Coup::new_with_config(
+//!     vec![Box::new(StaticBot), Box::new(StaticBot)],
+//!     Config {
+//!         roles: Some(roles),
+//!         ..Config::default()
+//!     },
+//! );
+//!
+//! coup_game.play();
+//! ```
+
+use std::mem::discriminant;
+
+use crate::{Action, Card, Counter};
+
+/// A role of the game made of a [Card] and everything it allows a bot to do.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Role {
+	/// The card of this role
+	pub card: Card,
+	/// The actions a bot can take by claiming this role and the coins they cost.
+	/// Only [Action::Assassination], [Action::Swapping], [Action::Stealing],
+	/// [Action::Tax] and [Action::Examine] need a role, the engine ignores any
+	/// other action.
+	/// The target of each action is ignored.
+	pub actions: Vec<(Action, u8)>,
+	/// The amount of cards a bot draws from the deck when it takes
+	/// [Action::Swapping] with this role, either 1 or 2.
+	/// Ignored for roles that don't allow [Action::Swapping].
+	pub exchange_cards: u8,
+	/// The actions a bot can take by claiming it doesn't have this role.
+	/// Only [Action::Embezzlement] is taken this way, the engine ignores any
+	/// other action.
+	/// The target of each action is ignored.
+	pub disclaimed_actions: Vec<Action>,
+	/// The actions of other bots this role can block
+	pub blocks: Vec<Counter>,
+}
+
+/// The set of roles a deck is made of.
+/// Each role appears in the deck the same amount of times.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoleSet {
+	/// All roles of this set
+	pub roles: Vec<Role>,
+}
+
+impl RoleSet {
+	/// The roles of the base game with the [Card::Ambassador].
+	pub fn classic() -> Self {
+		Self::with_exchange_role(Role {
+			card: Card::Ambassador,
+			actions: vec![(Action::Swapping, 0)],
+			exchange_cards: 2,
+			disclaimed_actions: vec![],
+			blocks: vec![Counter::Stealing],
+		})
+	}
+
+	/// The roles of the Reformation expansion with the [Card::Inquisitor]
+	/// replacing the [Card::Ambassador].
+	pub fn inquisitor() -> Self {
+		Self::with_exchange_role(Role {
+			card: Card::Inquisitor,
+			actions: vec![(Action::Swapping, 0), (Action::Examine(String::new()), 0)],
+			exchange_cards: 1,
+			disclaimed_actions: vec![],
+			blocks: vec![Counter::Stealing],
+		})
+	}
+
+	fn with_exchange_role(exchange_role: Role) -> Self {
+		Self {
+			roles: vec![
+				exchange_role,
+				Role {
+					card: Card::Assassin,
+					actions: vec![(Action::Assassination(String::new()), 3)],
+					exchange_cards: 0,
+					disclaimed_actions: vec![],
+					blocks: vec![],
+				},
+				Role {
+					card: Card::Captain,
+					actions: vec![(Action::Stealing(String::new()), 0)],
+					exchange_cards: 0,
+					disclaimed_actions: vec![],
+					blocks: vec![Counter::Stealing],
+				},
+				Role {
+					card: Card::Contessa,
+					actions: vec![],
+					exchange_cards: 0,
+					disclaimed_actions: vec![],
+					blocks: vec![Counter::Assassination],
+				},
+				Role {
+					card: Card::Duke,
+					actions: vec![(Action::Tax, 0)],
+					exchange_cards: 0,
+					disclaimed_actions: vec![Action::Embezzlement],
+					blocks: vec![Counter::ForeignAid],
+				},
+			],
+		}
+	}
+
+	/// The cards of all roles in this set, one of each.
+	pub fn cards(&self) -> Vec<Card> {
+		self.roles.iter().map(|role| role.card).collect()
+	}
+
+	/// A deck for a table of the given amount of players.
+	/// Tables of up to 6 players get 3 cards of each role, 7-8 players get 4
+	/// and 9-10 players get 5.
+	pub fn deck(&self, players: usize) -> Vec<Card> {
+		let copies = match players {
+			0..=6 => 3,
+			7..=8 => 4,
+			_ => 5,
+		};
+		self
+			.cards()
+			.iter()
+			.flat_map(|card| std::iter::repeat_n(*card, copies))
+			.collect()
+	}
+
+	/// The card a bot claims to have when taking this action.
+	/// Returns `None` for actions that don't need a role.
+	pub fn claimed_card(&self, action: &Action) -> Option<Card> {
+		self
+			.roles
+			.iter()
+			.find(|role| role.actions.iter().any(|(a, _)| same_action(a, action)))
+			.map(|role| role.card)
+	}
+
+	/// The card a bot claims NOT to have when taking this action.
+	pub fn disclaimed_card(&self, action: &Action) -> Option<Card> {
+		self
+			.roles
+			.iter()
+			.find(|role| {
+				role.disclaimed_actions.iter().any(|a| same_action(a, action))
+			})
+			.map(|role| role.card)
+	}
+
+	/// Whether any role in this set allows this action.
+	pub fn allows(&self, action: &Action) -> bool {
+		self.claimed_card(action).is_some()
+			|| self.disclaimed_card(action).is_some()
+	}
+
+	/// The cards that can block this action.
+	pub fn blockers(&self, action: &Action) -> Vec<Card> {
+		match Counter::from_action(action) {
+			Some(counter) => self
+				.roles
+				.iter()
+				.filter(|role| role.blocks.contains(&counter))
+				.map(|role| role.card)
+				.collect(),
+			None => vec![],
+		}
+	}
+
+	/// The amount of cards [Action::Swapping] draws from the deck.
+	pub fn exchange_cards(&self) -> u8 {
+		self
+			.roles
+			.iter()
+			.find(|role| {
+				role.actions.iter().any(|(a, _)| same_action(a, &Action::Swapping))
+			})
+			.map_or(2, |role| role.exchange_cards)
+	}
+
+	/// The coins this action costs the role that allows it.
+	pub fn cost(&self, action: &Action) -> u8 {
+		self
+			.roles
+			.iter()
+			.flat_map(|role| role.actions.iter())
+			.find(|(a, _)| same_action(a, action))
+			.map(|(_, cost)| *cost)
+			.unwrap_or(0)
+	}
+}
+
+// Actions are compared by kind and not by target
+fn same_action(a: &Action, b: &Action) -> bool {
+	discriminant(a) == discriminant(b)
+}
@@ -0,0 +1,244 @@
+//! The [ScoringRule] trait that decides how many points each bot gets for a
+//! game and the rules that ship with the engine.
+//!
+//! ```rust
+//! use coup::{bots::StaticBot, scoring::Placement, Coup};
+//!
+//! let mut coup_game =
+//!     Coup::new(vec![Box::new(StaticBot), Box::new(StaticBot)]);
+//! coup_game.set_scoring_rule(Placement);
+//!
+//! coup_game.looping(5);
+//! ```
+
+use std::{
+	cmp::Ordering,
+	fmt,
+	ops::{Add, AddAssign, Neg},
+};
+
+use crate::result::GameResult;
+
+/// An exact amount of points stored as a reduced fraction so adding up
+/// millions of games doesn't drift.
+/// Use [Points::to_f64] or [fmt::Display] only to show points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Points {
+	numerator: i128,
+	denominator: i128,
+}
+
+impl Points {
+	/// No points at all.
+	pub const ZERO: Self = Self {
+		numerator: 0,
+		denominator: 1,
+	};
+
+	/// The points `numerator/denominator`.
+	///
+	/// Panics if the denominator is zero.
+	pub fn new(numerator: i128, denominator: i128) -> Self {
+		assert!(denominator != 0, "Points can't have a denominator of zero");
+		let divisor = gcd(numerator, denominator) * denominator.signum();
+		Self {
+			numerator: numerator / divisor,
+			denominator: denominator / divisor,
+		}
+	}
+
+	/// The numerator of the reduced fraction.
+	pub fn numerator(&self) -> i128 {
+		self.numerator
+	}
+
+	/// The always positive denominator of the reduced fraction.
+	pub fn denominator(&self) -> i128 {
+		self.denominator
+	}
+
+	/// The closest floating point number to these points.
+	pub fn to_f64(&self) -> f64 {
+		self.numerator as f64 / self.denominator as f64
+	}
+}
+
+impl Default for Points {
+	fn default() -> Self {
+		Self::ZERO
+	}
+}
+
+impl From<i128> for Points {
+	fn from(points: i128) -> Self {
+		Self::new(points, 1)
+	}
+}
+
+impl Add for Points {
+	type Output = Self;
+
+	fn add(self, other: Self) -> Self {
+		// Dividing by the gcd first keeps the numbers small
+		let divisor = gcd(self.denominator, other.denominator);
+		let denominator = self.denominator / divisor * other.denominator;
+		Self::new(
+			self.numerator * (denominator / self.denominator)
+				+ other.numerator * (denominator / other.denominator),
+			denominator,
+		)
+	}
+}
+
+impl AddAssign for Points {
+	fn add_assign(&mut self, other: Self) {
+		*self = *self + other;
+	}
+}
+
+impl Neg for Points {
+	type Output = Self;
+
+	fn neg(self) -> Self {
+		Self {
+			numerator: -self.numerator,
+			denominator: self.denominator,
+		}
+	}
+}
+
+impl Ord for Points {
+	fn cmp(&self, other: &Self) -> Ordering {
+		(self.numerator * other.denominator)
+			.cmp(&(other.numerator * self.denominator))
+	}
+}
+
+impl PartialOrd for Points {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl fmt::Display for Points {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&self.to_f64(), f)
+	}
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+	let (mut a, mut b) = (a.abs(), b.abs());
+	while b != 0 {
+		(a, b) = (b, a % b);
+	}
+	a.max(1)
+}
+
+/// A rule that turns the [GameResult] of a single game into points.
+/// The engine adds these points to the score of each bot.
+pub trait ScoringRule {
+	/// The points each seated bot gets for this game.
+	/// Bots that aren't returned don't score.
+	fn score(&self, result: &GameResult) -> Vec<(String, Points)>;
+}
+
+/// The default zero-sum rule where all losers share -1 point and all winners
+/// share the same amount of positive points.
+/// In team play each team is scored as a single player and its members get an
+/// equal share.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ZeroSum;
+
+impl ScoringRule for ZeroSum {
+	fn score(&self, result: &GameResult) -> Vec<(String, Points)> {
+		let sides = result.sides();
+
+		let winner_count =
+			sides.iter().filter(|side| result.winners.contains(&side[0])).count()
+				as i128;
+		let loser_count = sides.len() as i128 - winner_count;
+		if loser_count == 0 {
+			// Nobody lost so nobody won either
+			return vec![];
+		}
+
+		// All losers share -1 point and all winners share +1 point
+		sides
+			.iter()
+			.flat_map(|side| {
+				let share = side.len() as i128;
+				let score = if result.winners.contains(&side[0]) {
+					Points::new(1, winner_count * share)
+				} else {
+					Points::new(-1, loser_count * share)
+				};
+				side.iter().map(move |name| (name.clone(), score))
+			})
+			.collect()
+	}
+}
+
+/// Placement from the elimination order (see [GameResult::placements]):
+/// winners get 1 point, the bot placed last gets 0 and everyone in between gets
+/// their share.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Placement;
+
+impl ScoringRule for Placement {
+	fn score(&self, result: &GameResult) -> Vec<(String, Points)> {
+		let players = result.seated().len();
+		if players < 2 {
+			return vec![];
+		}
+
+		result
+			.placements()
+			.into_iter()
+			.map(|(name, placement)| {
+				(name, Points::new((players - placement) as i128, players as i128 - 1))
+			})
+			.collect()
+	}
+}
+
+/// Counts wins and losses: 1 point for a win and -1 point for a loss.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WinLoss;
+
+impl ScoringRule for WinLoss {
+	fn score(&self, result: &GameResult) -> Vec<(String, Points)> {
+		result
+			.seated()
+			.into_iter()
+			.map(|name| {
+				let score = if result.winners.contains(&name) {
+					1
+				} else {
+					-1
+				};
+				(name, Points::from(score))
+			})
+			.collect()
+	}
+}
+
+/// Survival time: 1 point for each move a bot stayed in the game.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Survival;
+
+impl ScoringRule for Survival {
+	fn score(&self, result: &GameResult) -> Vec<(String, Points)> {
+		result
+			.seated()
+			.into_iter()
+			.map(|name| {
+				let survived = result
+					.eliminations
+					.iter()
+					.find(|elimination| elimination.name == name)
+					.map_or(result.moves, |elimination| elimination.turn);
+				(name, Points::from(survived as i128))
+			})
+			.collect()
+	}
+}
@@ -0,0 +1,343 @@
+//! The [PublicState] of a game folded from its [History].
+//!
+//! Bots only get the current [crate::bot::Context] but replaying the history
+//! gives the public state after each move so you can ask what another bot had
+//! when it did something.
+//!
+//! ```rust
+//! use coup::{state::PublicState, History};
+//!
+//! let history = vec![
+//!     History::ActionIncome {
+//!         by: String::from("Kate"),
+//!     },
+//!     History::ActionTax {
+//!         by: String::from("Tom"),
+//!     },
+//! ];
+//!
+//! let snapshots = PublicState::replay(&history);
+//! assert_eq!(snapshots.len(), 3);
+//! assert_eq!(snapshots[1].bot("Kate").unwrap().coins, 3);
+//! assert_eq!(snapshots[2].bot("Tom").unwrap().coins, 5);
+//! ```
+
+use crate::{role::RoleSet, Action, Card, History};
+
+/// Everything public about a game at a moment in time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublicState {
+	/// The amount of actions taken up to this state
+	pub moves: usize,
+	/// The amount of events of the history folded into this state
+	pub events: usize,
+	/// All bots of the game
+	pub bots: Vec<PublicBot>,
+	/// The coins in the treasury reserve of the Reformation expansion
+	pub treasury_reserve: u8,
+}
+
+/// Everything public about a bot at a moment in time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicBot {
+	/// The name of the bot
+	pub name: String,
+	/// The amount of coins this bot has
+	pub coins: u8,
+	/// The amount of cards this bot still has
+	pub cards: usize,
+	/// The cards this bot lost in the order it lost them
+	pub discards: Vec<Card>,
+}
+
+impl PublicState {
+	/// The state at the start of a game where each bot has 2 coins and 2 cards.
+	/// Change the bots of this state for handicaps or the two-player variant.
+	pub fn new(names: &[String]) -> Self {
+		Self {
+			moves: 0,
+			events: 0,
+			bots: names
+				.iter()
+				.map(|name| PublicBot {
+					name: name.clone(),
+					coins: 2,
+					cards: 2,
+					discards: vec![],
+				})
+				.collect(),
+			treasury_reserve: 0,
+		}
+	}
+
+	/// The public state after each move of a game with the classic roles where
+	/// each bot starts with 2 coins and 2 cards.
+	/// The first snapshot is the start of the game and each following snapshot
+	/// is the state after the next move.
+	pub fn replay(history: &[History]) -> Vec<Self> {
+		let mut names: Vec<String> = Vec::new();
+		for event in history {
+			let (by, target) = parties(event);
+			for name in std::iter::once(by).chain(target) {
+				if !names.iter().any(|known| known == name) {
+					names.push(name.clone());
+				}
+			}
+		}
+
+		Self::replay_from(Self::new(&names), history, &RoleSet::classic())
+	}
+
+	/// The public state after each move of a game starting from this state and
+	/// played with these roles.
+	///
+	/// A move starts with an action and includes all challenges, counters and
+	/// card losses that follow it.
+	/// Penalties don't show up as an action so they are part of the move before.
+	pub fn replay_from(
+		start: Self,
+		history: &[History],
+		roles: &RoleSet,
+	) -> Vec<Self> {
+		let mut snapshots = vec![start.clone()];
+		let mut state = start;
+		let mut turn: Option<Turn> = None;
+
+		for event in history {
+			match event {
+				History::ActionAssassination { .. }
+				| History::ActionCoup { .. }
+				| History::ActionForeignAid { .. }
+				| History::ActionSwapping { .. }
+				| History::ActionIncome { .. }
+				| History::ActionStealing { .. }
+				| History::ActionTax { .. }
+				| History::ActionConversion { .. }
+				| History::ActionEmbezzlement { .. }
+				| History::ActionExamine { .. } => {
+					if let Some(turn) = turn.take() {
+						state.finish(turn, roles);
+						snapshots.push(state.clone());
+					}
+					turn = Some(Turn::new(event.clone()));
+				},
+				History::ChallengeAssassin { .. }
+				| History::ChallengeAmbassador { .. }
+				| History::ChallengeCaptain { .. }
+				| History::ChallengeDuke { .. }
+				| History::ChallengeEmbezzlement { .. }
+				| History::ChallengeInquisitor { .. }
+				| History::ChallengeContessa { .. } => {
+					if let Some(turn) = &mut turn {
+						turn.awaiting = Awaiting::ActionChallenge;
+					}
+				},
+				History::CounterAssassination { by, .. }
+				| History::CounterForeignAid { by, .. }
+				| History::CounterStealing { by, .. } => {
+					if let Some(turn) = &mut turn {
+						turn.counterer = Some(by.clone());
+					}
+				},
+				History::CounterChallengeContessa { .. }
+				| History::CounterChallengeDuke { .. }
+				| History::CounterChallengeCaptainAmbassedor { .. }
+				| History::CounterChallengeAssassin { .. } => {
+					if let Some(turn) = &mut turn {
+						turn.awaiting = Awaiting::CounterChallenge;
+					}
+				},
+				History::CardLoss { by, card } => {
+					if let Some(bot) = state.bot_mut(by) {
+						bot.cards = bot.cards.saturating_sub(1);
+						bot.discards.push(*card);
+					}
+
+					// The first card loss after a challenge tells who was right
+					if let Some(turn) = &mut turn {
+						match turn.awaiting {
+							Awaiting::ActionChallenge => {
+								let (actor, target) = parties(&turn.action);
+								turn.caught = by == actor;
+								turn.target_alive = target
+									.and_then(|target| state.bot(target))
+									.is_none_or(|target| target.cards > 0);
+							},
+							Awaiting::CounterChallenge => {
+								turn.counter_failed = turn.counterer.as_ref() == Some(by);
+							},
+							Awaiting::Nothing => {},
+						}
+						turn.awaiting = Awaiting::Nothing;
+					}
+				},
+				History::ActionExamineSwap { .. } | History::Draft { .. } => {},
+			}
+			state.events += 1;
+		}
+
+		if let Some(turn) = turn.take() {
+			state.finish(turn, roles);
+			snapshots.push(state);
+		} else if let Some(last) = snapshots.last_mut() {
+			// Events before the first action don't change the public state
+			last.events = state.events;
+		}
+
+		snapshots
+	}
+
+	/// The bot with this name.
+	pub fn bot(&self, name: &str) -> Option<&PublicBot> {
+		self.bots.iter().find(|bot| bot.name == name)
+	}
+
+	fn bot_mut(&mut self, name: &str) -> Option<&mut PublicBot> {
+		self.bots.iter_mut().find(|bot| bot.name == name)
+	}
+
+	fn coins(&self, name: &str) -> u8 {
+		self.bot(name).map_or(0, |bot| bot.coins)
+	}
+
+	fn add_coins(&mut self, name: &str, coins: u8) {
+		if let Some(bot) = self.bot_mut(name) {
+			bot.coins = bot.coins.saturating_add(coins);
+		}
+	}
+
+	fn pay_coins(&mut self, name: &str, coins: u8) {
+		if let Some(bot) = self.bot_mut(name) {
+			bot.coins -= coins;
+		}
+	}
+
+	// The coins of a move change once all challenges and counters are resolved
+	fn finish(&mut self, turn: Turn, roles: &RoleSet) {
+		self.moves += 1;
+
+		let countered = turn.counterer.is_some() && !turn.counter_failed;
+		let performed = !turn.caught && turn.target_alive && !countered;
+		// The role cost of an action, paid before the action is performed
+		let fee = match &turn.action {
+			History::ActionAssassination { target, .. } => {
+				roles.cost(&Action::Assassination(target.clone()))
+			},
+			History::ActionStealing { target, .. } => {
+				roles.cost(&Action::Stealing(target.clone()))
+			},
+			History::ActionExamine { target, .. } => {
+				roles.cost(&Action::Examine(target.clone()))
+			},
+			History::ActionTax { .. } => roles.cost(&Action::Tax),
+			History::ActionSwapping { .. } => roles.cost(&Action::Swapping),
+			_ => 0,
+		};
+
+		// Bots without enough coins for a fee are penalized instead
+		match &turn.action {
+			History::ActionIncome { by } => self.add_coins(by, 1),
+			History::ActionForeignAid { by } if !countered => self.add_coins(by, 2),
+			History::ActionCoup { by, .. } if self.coins(by) >= 7 => {
+				self.pay_coins(by, 7)
+			},
+			History::ActionTax { by } if performed && self.coins(by) >= fee => {
+				self.pay_coins(by, fee);
+				self.add_coins(by, 3);
+			},
+			History::ActionAssassination { by, .. }
+			| History::ActionSwapping { by }
+			| History::ActionExamine { by, .. }
+				if performed && self.coins(by) >= fee =>
+			{
+				self.pay_coins(by, fee)
+			},
+			History::ActionStealing { by, target }
+				if performed && self.coins(by) >= fee =>
+			{
+				self.pay_coins(by, fee);
+				let booty = std::cmp::min(self.coins(target), 2);
+				self.pay_coins(target, booty);
+				self.add_coins(by, booty);
+			},
+			History::ActionConversion { by, target } => {
+				// Converting yourself is cheaper than converting someone else
+				let fee = if by == target { 1 } else { 2 };
+				if self.coins(by) >= fee {
+					self.pay_coins(by, fee);
+					self.treasury_reserve = self.treasury_reserve.saturating_add(fee);
+				}
+			},
+			History::ActionEmbezzlement { by } if !turn.caught => {
+				let reserve = self.treasury_reserve;
+				self.add_coins(by, reserve);
+				self.treasury_reserve = 0;
+			},
+			_ => {},
+		}
+	}
+}
+
+// The challenge whose card loss we are waiting for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Awaiting {
+	Nothing,
+	ActionChallenge,
+	CounterChallenge,
+}
+
+// A move in progress
+struct Turn {
+	action: History,
+	awaiting: Awaiting,
+	caught: bool,
+	target_alive: bool,
+	counterer: Option<String>,
+	counter_failed: bool,
+}
+
+impl Turn {
+	fn new(action: History) -> Self {
+		Self {
+			action,
+			awaiting: Awaiting::Nothing,
+			caught: false,
+			target_alive: true,
+			counterer: None,
+			counter_failed: false,
+		}
+	}
+}
+
+// The bot that caused this event and the bot it targeted if any
+fn parties(event: &History) -> (&String, Option<&String>) {
+	match event {
+		History::ActionAssassination { by, target }
+		| History::ActionCoup { by, target }
+		| History::ActionStealing { by, target }
+		| History::ActionConversion { by, target }
+		| History::ActionExamine { by, target }
+		| History::ActionExamineSwap { by, target }
+		| History::ChallengeAssassin { by, target }
+		| History::ChallengeAmbassador { by, target }
+		| History::ChallengeCaptain { by, target }
+		| History::ChallengeDuke { by, target }
+		| History::ChallengeEmbezzlement { by, target }
+		| History::ChallengeInquisitor { by, target }
+		| History::ChallengeContessa { by, target }
+		| History::CounterAssassination { by, target }
+		| History::CounterForeignAid { by, target }
+		| History::CounterStealing { by, target }
+		| History::CounterChallengeContessa { by, target }
+		| History::CounterChallengeDuke { by, target }
+		| History::CounterChallengeCaptainAmbassedor { by, target }
+		| History::CounterChallengeAssassin { by, target } => (by, Some(target)),
+		History::ActionForeignAid { by }
+		| History::ActionSwapping { by }
+		| History::ActionIncome { by }
+		| History::ActionTax { by }
+		| History::ActionEmbezzlement { by }
+		| History::Draft { by }
+		| History::CardLoss { by, .. } => (by, None),
+	}
+}
@@ -0,0 +1,301 @@
+//! A terminal [Viewer] to step through a [Replay] event by event.
+//!
+//! ```rust
+//! use coup::{bots::RandomBot, viewer::Viewer, Coup};
+//!
+//! let mut coup_game = Coup::new(vec![Box::new(RandomBot), Box::new(RandomBot)]);
+//! coup_game.set_replay(true);
+//! coup_game.play();
+//!
+//! let mut viewer = Viewer::new(&coup_game.replay()).unwrap();
+//! viewer.next_challenge();
+//! viewer.god_view = true;
+//! println!("{}", viewer.render());
+//!
+//! // Or let the user step through it with the keyboard
+//! // viewer.run();
+//! ```
+
+use std::{
+	fmt,
+	io::{self, BufRead, Write},
+};
+
+use crate::{
+	replay::{Replay, ReplayError},
+	Allegiance, Card, History,
+};
+
+/// Everything on the table right before an event happened, including the cards
+/// only the engine knows about.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+	/// All seated bots in seat order
+	pub bots: Vec<FrameBot>,
+	/// The cards left in the deck from bottom to top
+	pub deck: Vec<Card>,
+	/// The cards that were lost so far
+	pub discard_pile: Vec<Card>,
+	/// The coins in the treasury reserve of the Reformation expansion
+	pub treasury_reserve: u8,
+}
+
+/// A bot as it was on the table in a [Frame].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameBot {
+	/// The name of the bot
+	pub name: String,
+	/// The coins of the bot
+	pub coins: u8,
+	/// The hidden cards of the bot
+	pub cards: Vec<Card>,
+	/// The amount of cards this bot started the game with
+	pub starting_cards: usize,
+	/// The faction of the bot in the Reformation expansion
+	pub allegiance: Option<Allegiance>,
+}
+
+impl fmt::Display for FrameBot {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"\x1b[33m[\x1b[1m{}\x1b[0m \x1b[31m{}{}\x1b[33m 💰{}]\x1b[39m",
+			self.name,
+			"♥".repeat(self.cards.len()),
+			"♡".repeat(self.starting_cards.saturating_sub(self.cards.len())),
+			self.coins
+		)
+	}
+}
+
+/// Steps forward and backward through the events of a replayed game.
+///
+/// Position 0 is the table before the first event and position `n` is the
+/// table after the `n`th event.
+pub struct Viewer {
+	/// Show the hidden cards of each bot and the deck
+	pub god_view: bool,
+	history: Vec<History>,
+	frames: Vec<Frame>,
+	position: usize,
+}
+
+impl Viewer {
+	/// Play the replay again to get the table after each of its events.
+	pub fn new(replay: &Replay) -> Result<Self, ReplayError> {
+		let (_, coup) = replay.replayed(true)?;
+		Ok(Self {
+			god_view: false,
+			history: coup.history,
+			frames: coup.frames.unwrap_or_default(),
+			position: 0,
+		})
+	}
+
+	/// The current position between 0 and [Viewer::len].
+	pub fn position(&self) -> usize {
+		self.position
+	}
+
+	/// The amount of events of the game.
+	pub fn len(&self) -> usize {
+		self.history.len()
+	}
+
+	/// Whether the game didn't have a single event.
+	pub fn is_empty(&self) -> bool {
+		self.history.is_empty()
+	}
+
+	/// The event that led to the current position.
+	pub fn event(&self) -> Option<&History> {
+		self.position.checked_sub(1).map(|index| &self.history[index])
+	}
+
+	/// The table at the current position.
+	pub fn frame(&self) -> &Frame {
+		&self.frames[self.position]
+	}
+
+	/// Jump to a position, clamped to the end of the game.
+	pub fn seek(&mut self, position: usize) {
+		self.position = position.min(self.len());
+	}
+
+	/// Go to the next event. Returns false at the end of the game.
+	pub fn step(&mut self) -> bool {
+		self.jump(true, |_, _| true)
+	}
+
+	/// Go to the previous event. Returns false at the start of the game.
+	pub fn rewind(&mut self) -> bool {
+		self.jump(false, |_, _| true)
+	}
+
+	/// Go to the next challenge of an action or a counter.
+	pub fn next_challenge(&mut self) -> bool {
+		self.jump(true, Self::is_challenge)
+	}
+
+	/// Go to the previous challenge of an action or a counter.
+	pub fn previous_challenge(&mut self) -> bool {
+		self.jump(false, Self::is_challenge)
+	}
+
+	/// Go to the next event that eliminated a bot.
+	pub fn next_elimination(&mut self) -> bool {
+		self.jump(true, Self::is_elimination)
+	}
+
+	/// Go to the previous event that eliminated a bot.
+	pub fn previous_elimination(&mut self) -> bool {
+		self.jump(false, Self::is_elimination)
+	}
+
+	// Move to the closest position in a direction whose event matches
+	fn jump(
+		&mut self,
+		forward: bool,
+		matches: impl Fn(&Self, usize) -> bool,
+	) -> bool {
+		let found = if forward {
+			(self.position + 1..=self.len()).find(|position| matches(self, *position))
+		} else {
+			(0..self.position).rev().find(|position| matches(self, *position))
+		};
+		match found {
+			Some(position) => {
+				self.position = position;
+				true
+			},
+			None => false,
+		}
+	}
+
+	fn is_challenge(&self, position: usize) -> bool {
+		matches!(
+			position.checked_sub(1).map(|index| &self.history[index]),
+			Some(
+				History::ChallengeAssassin { .. }
+					| History::ChallengeAmbassador { .. }
+					| History::ChallengeCaptain { .. }
+					| History::ChallengeDuke { .. }
+					| History::ChallengeEmbezzlement { .. }
+					| History::ChallengeInquisitor { .. }
+					| History::ChallengeContessa { .. }
+					| History::CounterChallengeContessa { .. }
+					| History::CounterChallengeDuke { .. }
+					| History::CounterChallengeCaptainAmbassedor { .. }
+					| History::CounterChallengeAssassin { .. }
+			)
+		)
+	}
+
+	fn is_elimination(&self, position: usize) -> bool {
+		match position.checked_sub(1).map(|index| &self.history[index]) {
+			Some(History::CardLoss { by, .. }) => {
+				let cards = |frame: &Frame| {
+					frame
+						.bots
+						.iter()
+						.find(|bot| bot.name == *by)
+						.map_or(0, |bot| bot.cards.len())
+				};
+				cards(&self.frames[position - 1]) > 0
+					&& cards(&self.frames[position]) == 0
+			},
+			_ => false,
+		}
+	}
+
+	/// The table at the current position as text for the terminal.
+	pub fn render(&self) -> String {
+		let frame = self.frame();
+		let mut lines = vec![format!(
+			" \x1b[1mEvent {} of {}\x1b[0m{}",
+			self.position,
+			self.len(),
+			if self.god_view {
+				" \x1b[35m(god view)\x1b[39m"
+			} else {
+				""
+			}
+		)];
+		lines.push(match self.event() {
+			Some(event) => format!(" {:?}", event),
+			None => String::from(" The game is about to start"),
+		});
+		lines.push(String::new());
+
+		for bot in frame.bots.iter() {
+			let mut line = format!(" {}", bot);
+			if let Some(allegiance) = bot.allegiance {
+				line = format!("{} {:?}", line, allegiance);
+			}
+			if self.god_view {
+				line = format!("{} \x1b[90m{:?}\x1b[39m", line, bot.cards);
+			}
+			lines.push(line);
+		}
+
+		lines.push(String::new());
+		lines.push(format!(" Discard pile: {:?}", frame.discard_pile));
+		if frame.treasury_reserve > 0 {
+			lines.push(format!(" Treasury reserve: 💰{}", frame.treasury_reserve));
+		}
+		if self.god_view {
+			lines.push(format!(" Deck: {:?}", frame.deck));
+		}
+		lines.join("\n")
+	}
+
+	/// Step through the game in the terminal until the user quits.
+	///
+	/// Commands are read line by line: `n` or enter for the next event, `p` for
+	/// the previous event, `c`/`C` for the next/previous challenge, `e`/`E` for
+	/// the next/previous elimination, a number to jump to that event, `g` to
+	/// toggle the god view and `q` to quit.
+	pub fn run(&mut self) {
+		let stdin = io::stdin();
+		let mut input = stdin.lock();
+		loop {
+			print!(
+				"\x1b[2J\x1b[H{}\n\n \x1b[90m[n]ext [p]revious [c/C] challenge [e/E] elimination [g]od view [q]uit\x1b[39m\n > ",
+				self.render()
+			);
+			let _ = io::stdout().flush();
+
+			let mut line = String::new();
+			if input.read_line(&mut line).unwrap_or(0) == 0 {
+				break;
+			}
+			match line.trim() {
+				"" | "n" => {
+					self.step();
+				},
+				"p" => {
+					self.rewind();
+				},
+				"c" => {
+					self.next_challenge();
+				},
+				"C" => {
+					self.previous_challenge();
+				},
+				"e" => {
+					self.next_elimination();
+				},
+				"E" => {
+					self.previous_elimination();
+				},
+				"g" => self.god_view = !self.god_view,
+				"q" => break,
+				command => {
+					if let Ok(position) = command.parse() {
+						self.seek(position);
+					}
+				},
+			}
+		}
+	}
+}